@@ -0,0 +1,244 @@
+//! In crate implementation of `ValidationType::LiteralChecker`, so every evaluator build
+//! compares outputs the same way.
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::status::Status;
+
+/// Longest token copied into a `Difference`.
+const MAX_DIFFERENCE_TOKEN_SIZE: usize = 32;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+#[ts(export)]
+pub enum LiteralCheckMode {
+    /// byte by byte comparison
+    Exact,
+    /// compares whitespace separated tokens, the amount and kind of whitespace is ignored
+    #[default]
+    Tokens,
+    /// like `Tokens` ignoring ascii case
+    CaseInsensitive,
+    /// like `Tokens`, numeric tokens are accepted when they are within either epsilon
+    Float { absolute_epsilon: f64, relative_epsilon: f64 },
+}
+
+/// First place where the contestant output differs from the expected one, `line` and
+/// `column` start at 1 and point into the contestant output.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Difference {
+    pub line: usize,
+    pub column: usize,
+    /// `None` when the expected output ended first
+    pub expected: Option<String>,
+    /// `None` when the contestant output ended first
+    pub actual: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct LiteralCheckResult {
+    pub status: Status,
+    pub difference: Option<Difference>,
+}
+
+impl LiteralCheckResult {
+    fn accepted() -> Self {
+        Self { status: Status::Accepted, difference: None }
+    }
+
+    fn wrong_answer(difference: Difference) -> Self {
+        Self { status: Status::WrongAnswer, difference: Some(difference) }
+    }
+}
+
+pub fn literal_check(
+    expected: &[u8],
+    actual: &[u8],
+    mode: &LiteralCheckMode,
+) -> LiteralCheckResult {
+    match mode {
+        LiteralCheckMode::Exact => exact_check(expected, actual),
+        _ => token_check(expected, actual, mode),
+    }
+}
+
+fn exact_check(expected: &[u8], actual: &[u8]) -> LiteralCheckResult {
+    let Some(position) = first_difference(expected, actual) else {
+        return LiteralCheckResult::accepted();
+    };
+    let (line, column) = location(actual, position);
+    LiteralCheckResult::wrong_answer(Difference {
+        line,
+        column,
+        expected: excerpt(expected, position),
+        actual: excerpt(actual, position),
+    })
+}
+
+fn token_check(expected: &[u8], actual: &[u8], mode: &LiteralCheckMode) -> LiteralCheckResult {
+    let mut expected_tokens = Tokens::new(expected);
+    let mut actual_tokens = Tokens::new(actual);
+    loop {
+        match (expected_tokens.next(), actual_tokens.next()) {
+            (None, None) => return LiteralCheckResult::accepted(),
+            (Some(expected_token), Some(actual_token))
+                if tokens_match(expected_token.text, actual_token.text, mode) => {},
+            (expected_token, actual_token) => {
+                let (line, column) = match &actual_token {
+                    Some(token) => (token.line, token.column),
+                    None => location(actual, actual.len()),
+                };
+                return LiteralCheckResult::wrong_answer(Difference {
+                    line,
+                    column,
+                    expected: expected_token.map(|token| truncate(token.text)),
+                    actual: actual_token.map(|token| truncate(token.text)),
+                });
+            },
+        }
+    }
+}
+
+/// Position of the first byte that differs, `None` when both are equal.
+pub(crate) fn first_difference(expected: &[u8], actual: &[u8]) -> Option<usize> {
+    expected
+        .iter()
+        .zip(actual)
+        .position(|(expected, actual)| expected != actual)
+        .or_else(|| (expected.len() != actual.len()).then(|| expected.len().min(actual.len())))
+}
+
+pub(crate) fn tokens_match(expected: &[u8], actual: &[u8], mode: &LiteralCheckMode) -> bool {
+    match mode {
+        LiteralCheckMode::Exact | LiteralCheckMode::Tokens => expected == actual,
+        LiteralCheckMode::CaseInsensitive => expected.eq_ignore_ascii_case(actual),
+        LiteralCheckMode::Float { absolute_epsilon, relative_epsilon } => {
+            match (parse_float(expected), parse_float(actual)) {
+                (Some(expected), Some(actual)) => {
+                    let error = (expected - actual).abs();
+                    error <= *absolute_epsilon || error <= relative_epsilon * expected.abs()
+                },
+                _ => expected == actual,
+            }
+        },
+    }
+}
+
+fn parse_float(token: &[u8]) -> Option<f64> {
+    std::str::from_utf8(token).ok()?.parse::<f64>().ok().filter(|value| value.is_finite())
+}
+
+pub(crate) struct Token<'a> {
+    pub(crate) text: &'a [u8],
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// Whitespace separated tokens along with their position.
+pub(crate) struct Tokens<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    line: usize,
+    line_start: usize,
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0, line: 1, line_start: 0 }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.bytes.len() && self.bytes[self.position].is_ascii_whitespace() {
+            if self.bytes[self.position] == b'\n' {
+                self.line += 1;
+                self.line_start = self.position + 1;
+            }
+            self.position += 1;
+        }
+        if self.position == self.bytes.len() {
+            return None;
+        }
+        let start = self.position;
+        while self.position < self.bytes.len() && !self.bytes[self.position].is_ascii_whitespace() {
+            self.position += 1;
+        }
+        Some(Token {
+            text: &self.bytes[start..self.position],
+            line: self.line,
+            column: start - self.line_start + 1,
+        })
+    }
+}
+
+fn location(bytes: &[u8], position: usize) -> (usize, usize) {
+    let before = &bytes[..position.min(bytes.len())];
+    let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+    let line_start =
+        before.iter().rposition(|byte| *byte == b'\n').map_or(0, |newline| newline + 1);
+    (line, before.len() - line_start + 1)
+}
+
+fn excerpt(bytes: &[u8], position: usize) -> Option<String> {
+    (position < bytes.len()).then(|| truncate(&bytes[position..]))
+}
+
+pub(crate) fn truncate(bytes: &[u8]) -> String {
+    truncate_to(bytes, MAX_DIFFERENCE_TOKEN_SIZE)
+}
+
+pub(crate) fn truncate_to(bytes: &[u8], max: usize) -> String {
+    String::from_utf8_lossy(&bytes[..bytes.len().min(max)]).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{literal_check, LiteralCheckMode};
+    use crate::status::Status;
+
+    #[test]
+    fn exact_mode_reports_first_different_byte() {
+        let result = literal_check(b"1 2\n3 4\n", b"1 2\n3 5\n", &LiteralCheckMode::Exact);
+        assert_eq!(result.status, Status::WrongAnswer);
+        let difference = result.difference.unwrap();
+        assert_eq!((difference.line, difference.column), (2, 3));
+
+        let result = literal_check(b"1 2\n", b"1 2", &LiteralCheckMode::Exact);
+        assert_eq!(result.status, Status::WrongAnswer);
+    }
+
+    #[test]
+    fn token_modes_ignore_whitespace() {
+        let result = literal_check(b"1 2\n3\n", b"1   2 3", &LiteralCheckMode::Tokens);
+        assert_eq!(result.status, Status::Accepted);
+
+        let result = literal_check(b"YES\n", b"yes\n", &LiteralCheckMode::Tokens);
+        assert_eq!(result.status, Status::WrongAnswer);
+        let result = literal_check(b"YES\n", b"yes\n", &LiteralCheckMode::CaseInsensitive);
+        assert_eq!(result.status, Status::Accepted);
+
+        let result = literal_check(b"1 2 3\n", b"1\n2\n", &LiteralCheckMode::Tokens);
+        let difference = result.difference.unwrap();
+        assert_eq!(difference.expected.as_deref(), Some("3"));
+        assert_eq!(difference.actual, None);
+        assert_eq!((difference.line, difference.column), (3, 1));
+    }
+
+    #[test]
+    fn float_mode_accepts_values_within_epsilon() {
+        let mode = LiteralCheckMode::Float { absolute_epsilon: 1e-6, relative_epsilon: 1e-9 };
+        assert_eq!(literal_check(b"0.333333\n", b"0.3333333\n", &mode).status, Status::Accepted);
+        assert_eq!(
+            literal_check(b"1000000000\n", b"1000000000.5\n", &mode).status,
+            Status::Accepted
+        );
+        assert_eq!(literal_check(b"0.5 ok\n", b"0.5 OK\n", &mode).status, Status::WrongAnswer);
+        let result = literal_check(b"1.0\n", b"1.1\n", &mode);
+        assert_eq!(result.difference.unwrap().actual.as_deref(), Some("1.1"));
+    }
+}
@@ -0,0 +1,205 @@
+use chrono::{
+    serde::{ts_milliseconds, ts_milliseconds_option},
+    DateTime, Utc,
+};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    problem::{ContestId, ProblemId},
+    relations::Relations,
+};
+
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, TS, sqlx::Type)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "clarification_visibility", rename_all = "snake_case")]
+pub enum ClarificationVisibility {
+    /// only the asker and the judges can read it
+    #[default]
+    Private,
+    /// every participant of the contest can read it
+    Broadcast,
+}
+
+/// A question asked by a participant during a contest.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Clarification {
+    pub id: Uuid,
+    pub contest_id: ContestId,
+    /// `None` for general questions about the contest
+    pub problem_id: Option<ProblemId>,
+    pub asker: Uuid,
+    pub question: String,
+    pub answer: Option<String>,
+    pub answered_by: Option<Uuid>,
+    pub visibility: ClarificationVisibility,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default, with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub answered_at: Option<DateTime<Utc>>,
+}
+
+impl Clarification {
+    pub fn is_answered(&self) -> bool {
+        self.answer.is_some()
+    }
+
+    /// `relations` are the relations the user holds with the clarification's contest.
+    pub fn is_visible_to(&self, user_id: &Uuid, relations: &[Relations]) -> bool {
+        self.asker == *user_id
+            || can_answer_clarifications(relations)
+            || (self.is_answered() && self.visibility == ClarificationVisibility::Broadcast)
+    }
+
+    pub fn answer(&mut self, form: ClarificationAnswerForm, judge: Uuid) {
+        self.answer = Some(form.answer);
+        self.visibility = form.visibility;
+        self.answered_by = Some(judge);
+        self.answered_at = Some(Utc::now());
+    }
+}
+
+/// A message sent by the judges to every participant of a contest.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Announcement {
+    pub id: Uuid,
+    pub contest_id: ContestId,
+    pub problem_id: Option<ProblemId>,
+    pub author: Uuid,
+    pub title: String,
+    pub body: String,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS, Validate)]
+#[ts(export)]
+pub struct ClarificationForm {
+    pub contest_id: u32,
+    #[serde(default)]
+    pub problem_id: Option<u32>,
+    #[validate(length(min = 1, max = 1000))]
+    pub question: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS, Validate)]
+#[ts(export)]
+pub struct ClarificationAnswerForm {
+    pub clarification_id: Uuid,
+    #[validate(length(min = 1, max = 2000))]
+    pub answer: String,
+    #[serde(default)]
+    pub visibility: ClarificationVisibility,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS, Validate)]
+#[ts(export)]
+pub struct AnnouncementForm {
+    pub contest_id: u32,
+    #[serde(default)]
+    pub problem_id: Option<u32>,
+    #[validate(length(min = 1, max = 100))]
+    pub title: String,
+    #[validate(length(min = 1, max = 2000))]
+    pub body: String,
+}
+
+/// Only the judges of a contest (its owners, problem setters and admins) may answer
+/// clarifications or publish announcements.
+pub fn can_answer_clarifications(relations: &[Relations]) -> bool {
+    relations.iter().any(|relation| {
+        matches!(
+            relation,
+            Relations::Owner | Relations::ProblemSetter | Relations::Admin
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::{
+        can_answer_clarifications, Clarification, ClarificationAnswerForm, ClarificationVisibility,
+    };
+    use crate::{problem::ContestId, relations::Relations};
+
+    fn clarification(asker: Uuid) -> Clarification {
+        Clarification {
+            id: Uuid::new_v4(),
+            contest_id: ContestId(1),
+            problem_id: None,
+            asker,
+            question: "¿Los números pueden ser negativos?".to_string(),
+            answer: None,
+            answered_by: None,
+            visibility: ClarificationVisibility::Private,
+            created_at: Utc::now(),
+            answered_at: None,
+        }
+    }
+
+    fn answer(clarification: &mut Clarification, visibility: ClarificationVisibility) {
+        let form = ClarificationAnswerForm {
+            clarification_id: clarification.id,
+            answer: "No".to_string(),
+            visibility,
+        };
+        clarification.answer(form, Uuid::new_v4());
+    }
+
+    #[test]
+    fn private_clarifications_are_only_visible_to_the_asker_and_judges() {
+        let asker = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let participant = [Relations::Participant];
+        let mut clarification = clarification(asker);
+
+        assert!(clarification.is_visible_to(&asker, &participant));
+        assert!(!clarification.is_visible_to(&other, &participant));
+        assert!(clarification.is_visible_to(&other, &[Relations::Owner]));
+
+        answer(&mut clarification, ClarificationVisibility::Private);
+        assert!(clarification.is_answered());
+        assert!(clarification.is_visible_to(&asker, &participant));
+        assert!(!clarification.is_visible_to(&other, &participant));
+        assert!(clarification.is_visible_to(&other, &[Relations::ProblemSetter]));
+    }
+
+    #[test]
+    fn broadcast_clarifications_are_visible_once_answered() {
+        let asker = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let participant = [Relations::Participant];
+        let mut clarification = clarification(asker);
+        clarification.visibility = ClarificationVisibility::Broadcast;
+
+        assert!(!clarification.is_visible_to(&other, &participant));
+
+        answer(&mut clarification, ClarificationVisibility::Broadcast);
+        assert!(clarification.is_visible_to(&asker, &participant));
+        assert!(clarification.is_visible_to(&other, &participant));
+        assert!(clarification.is_visible_to(&other, &[]));
+    }
+
+    #[test]
+    fn only_judges_answer_clarifications() {
+        assert!(can_answer_clarifications(&[Relations::Owner]));
+        assert!(can_answer_clarifications(&[Relations::ProblemSetter]));
+        assert!(can_answer_clarifications(&[
+            Relations::Participant,
+            Relations::Admin
+        ]));
+        assert!(!can_answer_clarifications(&[Relations::Participant]));
+        assert!(!can_answer_clarifications(&[]));
+    }
+}
@@ -5,7 +5,7 @@ pub const USERNAME_FORBIDDEN_CHARACTERS: [char; 10] =
 
 pub const MAX_SUBMISSION_FILE_SIZE_IN_BYTES: usize = 71680; // 70kb
 pub const MAX_SUBMISSION_FILE_SIZE_IN_KB: usize = 70;
-pub const MAX_TESCASE_FILE_SIZE_IN_BYTES: usize = 1048576; // 10MB
+pub const MAX_TESCASE_FILE_SIZE_IN_BYTES: usize = 1048576; // 1MiB
 pub const SUBMISSION_ID_BITS: u128 = 128;
 pub const TIMESTAMP_BITS: u128 = 41;
 pub const CONTEST_ID_BITS: u128 = 32;
@@ -16,3 +16,21 @@ pub const EMPTY_BITS: u128 =
 pub const CONTEST_MIN_DURATION_IN_SECONDS: i64 = 300; // 5 minutes
 pub const CONTEST_MAX_DURATION_IN_SECONDS: i64 = 60 * 60 * 24 * 30;// 1 month in seconds
 pub const MAX_PROBLEMS_PER_CONTEST: usize = 50;
+pub const TEAM_MIN_MEMBERS: usize = 1;
+pub const TEAM_MAX_MEMBERS: usize = 3;
+pub const INVITATION_TOKEN_SIZE: u64 = 32;
+pub const REGISTRATION_PASSWORD_HASH_ITERATIONS: usize = 100_000;
+pub const REGISTRATION_PASSWORD_SALT_SIZE: usize = 16;
+pub const CONTEST_BODY_VERSION: u32 = 2;
+pub const DEFAULT_LOCALE: &str = "es";
+pub const PROBLEM_ARCHIVE_VERSION: u32 = 2;
+pub const MAX_ARCHIVE_METADATA_SIZE_IN_BYTES: u64 = 4194304; // 4MiB
+pub const MAX_DIFF_MISMATCHES: usize = 10;
+pub const MAX_CHECKER_FILE_SIZE_IN_BYTES: u64 = 65536; // 64KiB
+pub const IDLENESS_LIMIT_FACTOR: u32 = 3;
+pub const MAX_INTERACTION_TRANSCRIPT_SIZE: usize = 4096; // 4KiB
+pub const MAX_VALIDATOR_MESSAGE_SIZE: usize = 256;
+pub const MAX_GENERATOR_SCRIPT_SIZE: u64 = 65536; // 64KiB
+pub const DEFAULT_PROBLEMS_PER_PAGE: u32 = 20;
+pub const MAX_PROBLEMS_PER_PAGE: u32 = 100;
+pub const MAX_TAGS_PER_PROBLEM: u64 = 8;
@@ -1,9 +1,24 @@
-use chrono::serde::ts_milliseconds;
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{
+    serde::{ts_milliseconds, ts_milliseconds_option},
+    DateTime, Utc,
+};
+use openssl::error::ErrorStack;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
+use validator::{Validate, ValidationError, ValidationErrors};
 
-use crate::problem::{ContestId, ProblemId, SubmissionId};
+use crate::{
+    consts::{
+        CONTEST_BODY_VERSION, CONTEST_MAX_DURATION_IN_SECONDS, CONTEST_MIN_DURATION_IN_SECONDS,
+        MAX_PROBLEMS_PER_CONTEST,
+    },
+    problem::{ContestId, ProblemId, SubmissionId},
+    registration::{hash_registration_password, verify_registration_password},
+    utils::empty_string_as_none,
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize, TS, Hash, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -55,6 +70,12 @@ pub struct Submission {
     pub problem_id: ProblemId,
     pub user_id: Uuid,
     pub contest_id: Option<ContestId>,
+    /// team the submission counts for, `None` for individual participation
+    #[serde(default)]
+    pub team_id: Option<Uuid>,
+    /// `ProblemRevision` the submission is judged against, rejudges keep it
+    #[serde(default = "crate::problem::first_revision")]
+    pub problem_revision: u32,
     pub language: Language,
     pub code: Vec<u8>,
     #[serde(
@@ -87,6 +108,8 @@ where
 pub struct Contest {
     pub id: ContestId,
     pub author: Uuid,
+    /// stored bodies are brought up to `CONTEST_BODY_VERSION` when read
+    #[serde(deserialize_with = "migrated_contest_body")]
     pub body: ContestBody,
     pub name: String,
     #[serde(with = "ts_milliseconds")]
@@ -94,11 +117,364 @@ pub struct Contest {
     #[serde(with = "ts_milliseconds")]
     pub end_date: chrono::DateTime<chrono::Utc>,
     pub contest_type: ContestType,
-    pub problems: Vec<ProblemId>,
+    /// contests stored before problems had labels hold a list of problem ids instead
+    #[serde(deserialize_with = "contest_problems_or_problem_ids")]
+    pub problems: Vec<ContestProblem>,
+    pub is_frozen: bool,
+    pub frozen_time: i32,
+    #[serde(default)]
+    pub registration_policy: RegistrationPolicy,
+    /// registration opens right away when `None`
+    #[serde(default, with = "ts_milliseconds_option")]
+    pub registration_start: Option<chrono::DateTime<chrono::Utc>>,
+    /// registration closes at `end_date` when `None`
+    #[serde(default, with = "ts_milliseconds_option")]
+    pub registration_end: Option<chrono::DateTime<chrono::Utc>>,
+    /// never sent to clients, responses go through `ContestGetResponse`
+    #[serde(default)]
+    pub registration_password_hash: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct ContestGetResponse {
+    pub id: ContestId,
+    pub author: Uuid,
+    pub body: ContestBody,
+    pub name: String,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub start_date: DateTime<Utc>,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub end_date: DateTime<Utc>,
+    pub contest_type: ContestType,
+    pub problems: Vec<ContestProblem>,
+    pub is_frozen: bool,
+    pub frozen_time: i32,
+    pub registration_policy: RegistrationPolicy,
+    #[serde(with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub registration_start: Option<DateTime<Utc>>,
+    #[serde(with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub registration_end: Option<DateTime<Utc>>,
+}
+
+impl From<&Contest> for ContestGetResponse {
+    fn from(contest: &Contest) -> Self {
+        Self {
+            id: contest.id.clone(),
+            author: contest.author,
+            body: contest.body.clone(),
+            name: contest.name.clone(),
+            start_date: contest.start_date,
+            end_date: contest.end_date,
+            contest_type: contest.contest_type.clone(),
+            problems: contest.problems.clone(),
+            is_frozen: contest.is_frozen,
+            frozen_time: contest.frozen_time,
+            registration_policy: contest.registration_policy.clone(),
+            registration_start: contest.registration_start,
+            registration_end: contest.registration_end,
+        }
+    }
+}
+
+/// Input used to create or update a contest, the server fills `id` and `author`.
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export)]
+pub struct ContestForm {
+    pub name: String,
+    pub body: ContestBody,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub start_date: DateTime<Utc>,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub end_date: DateTime<Utc>,
+    pub contest_type: ContestType,
+    pub problems: Vec<ContestProblem>,
     pub is_frozen: bool,
-    pub frozen_time: i32
+    /// seconds before `end_date` in which the scoreboard is frozen
+    pub frozen_time: i32,
+    #[serde(default)]
+    pub registration_policy: RegistrationPolicy,
+    #[serde(default, with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub registration_start: Option<DateTime<Utc>>,
+    #[serde(default, with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub registration_end: Option<DateTime<Utc>>,
+    /// required when `registration_policy` is `password`
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub registration_password: Option<String>,
+}
+
+/// How users join a contest.
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, TS, sqlx::Type)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "registration_policy", rename_all = "snake_case")]
+pub enum RegistrationPolicy {
+    #[default]
+    Open,
+    Password,
+    InviteOnly,
+    Approval,
+}
+
+/// A problem as it appears inside a contest, `label` is what the scoreboard shows ("A", "B", ...).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Validate, TS)]
+#[ts(export)]
+pub struct ContestProblem {
+    pub problem_id: ProblemId,
+    #[validate(length(min = 1, max = 3))]
+    pub label: String,
+    /// css hex color of the balloon, i.e. `#ff0000`
+    #[validate(custom = "validate_hex_color")]
+    pub color: Option<String>,
+    pub points: Option<u32>,
+    #[validate(range(min = 1, max = 10))] // time limit in seconds
+    pub time_limit_override: Option<u16>,
+    #[serde(default, with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub visible_from: Option<DateTime<Utc>>,
+}
+
+impl ContestProblem {
+    pub fn new(problem_id: ProblemId, index: usize) -> Self {
+        Self {
+            problem_id,
+            label: contest_problem_label(index),
+            color: None,
+            points: None,
+            time_limit_override: None,
+            visible_from: None,
+        }
+    }
+
+    /// Builds the problem list of a contest labeling each problem by its position.
+    pub fn from_problem_ids(problems: Vec<ProblemId>) -> Vec<Self> {
+        problems
+            .into_iter()
+            .enumerate()
+            .map(|(index, problem_id)| Self::new(problem_id, index))
+            .collect()
+    }
+
+    pub fn is_visible(&self, now: &DateTime<Utc>) -> bool {
+        self.visible_from.is_none_or(|visible_from| visible_from <= *now)
+    }
+}
+
+fn contest_problems_or_problem_ids<'de, D>(deserializer: D) -> Result<Vec<ContestProblem>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        ContestProblems(Vec<ContestProblem>),
+        ProblemIds(Vec<ProblemId>),
+    }
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::ContestProblems(problems) => problems,
+        Stored::ProblemIds(problem_ids) => ContestProblem::from_problem_ids(problem_ids),
+    })
+}
+
+/// Spreadsheet like labels: 0 -> "A", 25 -> "Z", 26 -> "AA", 27 -> "AB" ...
+pub fn contest_problem_label(index: usize) -> String {
+    let mut label = Vec::new();
+    let mut index = index + 1;
+    while index > 0 {
+        index -= 1;
+        label.push(b'A' + (index % 26) as u8);
+        index /= 26;
+    }
+    label.reverse();
+    String::from_utf8(label).unwrap()
+}
+
+fn validate_hex_color(color: &str) -> Result<(), ValidationError> {
+    let digits = color.strip_prefix('#').unwrap_or_default();
+    if digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_color"))
+    }
+}
+
+fn validate_contest_problems(problems: &[ContestProblem]) -> Result<(), ValidationError> {
+    if problems.len() > MAX_PROBLEMS_PER_CONTEST {
+        let mut error = ValidationError::new("too_many_problems");
+        error.add_param("max".into(), &MAX_PROBLEMS_PER_CONTEST);
+        error.add_param("value".into(), &problems.len());
+        return Err(error);
+    }
+    let mut seen = HashSet::with_capacity(problems.len());
+    if let Some(repeated) = problems.iter().find(|problem| !seen.insert(&problem.problem_id)) {
+        let mut error = ValidationError::new("repeated_problem");
+        error.add_param("value".into(), &repeated.problem_id.as_u32());
+        return Err(error);
+    }
+    let mut seen = HashSet::with_capacity(problems.len());
+    if let Some(repeated) = problems.iter().find(|problem| !seen.insert(&problem.label)) {
+        let mut error = ValidationError::new("repeated_label");
+        error.add_param("value".into(), &repeated.label);
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn validate_length(
+    errors: &mut ValidationErrors,
+    field: &'static str,
+    value: &str,
+    min: u64,
+    max: u64,
+) {
+    if !validator::validate_length(value, Some(min), Some(max), None) {
+        let mut error = ValidationError::new("length");
+        error.add_param("min".into(), &min);
+        error.add_param("max".into(), &max);
+        error.add_param("value".into(), &value);
+        errors.add(field, error);
+    }
+}
+
+fn validate_schedule(
+    errors: &mut ValidationErrors,
+    start_date: &DateTime<Utc>,
+    end_date: &DateTime<Utc>,
+    is_frozen: bool,
+    frozen_time: i32,
+) {
+    if end_date <= start_date {
+        errors.add("start_date", ValidationError::new("start_date_after_end_date"));
+        errors.add("end_date", ValidationError::new("end_date_before_start_date"));
+        return;
+    }
+    let duration = (*end_date - *start_date).num_seconds();
+    if duration < CONTEST_MIN_DURATION_IN_SECONDS {
+        let mut error = ValidationError::new("contest_too_short");
+        error.add_param("min".into(), &CONTEST_MIN_DURATION_IN_SECONDS);
+        error.add_param("value".into(), &duration);
+        errors.add("end_date", error);
+    } else if duration > CONTEST_MAX_DURATION_IN_SECONDS {
+        let mut error = ValidationError::new("contest_too_long");
+        error.add_param("max".into(), &CONTEST_MAX_DURATION_IN_SECONDS);
+        error.add_param("value".into(), &duration);
+        errors.add("end_date", error);
+    }
+    if is_frozen && !(0..=duration).contains(&i64::from(frozen_time)) {
+        let mut error = ValidationError::new("freeze_outside_contest");
+        error.add_param("max".into(), &duration);
+        error.add_param("value".into(), &frozen_time);
+        errors.add("frozen_time", error);
+    }
+}
+
+fn validate_registration_window(
+    errors: &mut ValidationErrors,
+    registration_start: Option<&DateTime<Utc>>,
+    registration_end: Option<&DateTime<Utc>>,
+    end_date: &DateTime<Utc>,
+) {
+    if let (Some(start), Some(end)) = (registration_start, registration_end) {
+        if end <= start {
+            errors.add(
+                "registration_end",
+                ValidationError::new("registration_end_before_registration_start"),
+            );
+        }
+    }
+    if registration_start.is_some_and(|start| start >= end_date) {
+        errors.add(
+            "registration_start",
+            ValidationError::new("registration_start_after_contest_end"),
+        );
+    }
+    if registration_end.is_some_and(|end| end > end_date) {
+        errors.add("registration_end", ValidationError::new("registration_end_after_contest_end"));
+    }
+}
+
+/// Adds the errors of `problems` and of every problem in it, the way `#[validate]` does for
+/// nested fields.
+fn validate_problems(
+    mut errors: ValidationErrors,
+    problems: &[ContestProblem],
+) -> Result<(), ValidationErrors> {
+    if let Err(error) = validate_contest_problems(problems) {
+        errors.add("problems", error);
+    }
+    let result = if errors.is_empty() { Ok(()) } else { Err(errors) };
+    if ValidationErrors::has_error(&result, "problems") {
+        return result;
+    }
+    let results = problems
+        .iter()
+        .map(|problem| ValidationErrors::merge(Ok(()), "problems", problem.validate()))
+        .collect();
+    ValidationErrors::merge_all(result, "problems", results)
 }
 
+// Implemented by hand instead of derived because schema level errors always end up under
+// `__all__`, the schedule errors belong to the date fields.
+impl Validate for Contest {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_length(&mut errors, "name", &self.name, 1, 100);
+        validate_schedule(
+            &mut errors,
+            &self.start_date,
+            &self.end_date,
+            self.is_frozen,
+            self.frozen_time,
+        );
+        validate_registration_window(
+            &mut errors,
+            self.registration_start.as_ref(),
+            self.registration_end.as_ref(),
+            &self.end_date,
+        );
+        validate_problems(errors, &self.problems)
+    }
+}
+
+impl Validate for ContestForm {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        validate_length(&mut errors, "name", &self.name, 1, 100);
+        validate_schedule(
+            &mut errors,
+            &self.start_date,
+            &self.end_date,
+            self.is_frozen,
+            self.frozen_time,
+        );
+        validate_registration_window(
+            &mut errors,
+            self.registration_start.as_ref(),
+            self.registration_end.as_ref(),
+            &self.end_date,
+        );
+        match &self.registration_password {
+            Some(password) => {
+                validate_length(&mut errors, "registration_password", password, 4, 64)
+            },
+            None if self.registration_policy == RegistrationPolicy::Password => errors.add(
+                "registration_password",
+                ValidationError::new("missing_registration_password"),
+            ),
+            None => (),
+        }
+        validate_problems(errors, &self.problems)
+    }
+}
 
 pub enum ContestState {
     NotStarted,
@@ -116,29 +492,187 @@ impl Contest {
             ContestState::Ended
         }
     }
+
+    pub fn is_registration_open(&self, now: &DateTime<Utc>) -> bool {
+        let start = self.registration_start.as_ref();
+        let end = self.registration_end.as_ref().unwrap_or(&self.end_date);
+        start.is_none_or(|start| start <= now) && now < end
+    }
+
+    /// Stores the hash of the password given in `ContestForm::registration_password`.
+    pub fn set_registration_password(&mut self, password: Option<&str>) -> Result<(), ErrorStack> {
+        self.registration_password_hash = password.map(hash_registration_password).transpose()?;
+        Ok(())
+    }
+
+    /// Whether `password` lets a user register, only password protected contests check it.
+    pub fn check_registration_password(&self, password: Option<&str>) -> bool {
+        if self.registration_policy != RegistrationPolicy::Password {
+            return true;
+        }
+        match (password, &self.registration_password_hash) {
+            (Some(password), Some(hash)) => verify_registration_password(password, hash),
+            _ => false,
+        }
+    }
 }
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 #[serde(rename_all = "lowercase")]
+#[ts(export)]
 pub struct ContestBody {
+    #[serde(default = "current_contest_body_version")]
+    pub version: u32,
+    #[serde(default)]
     pub information: String,
+    #[serde(default)]
     pub rules: String,
+    #[serde(default)]
     pub sponsor: String,
+    #[serde(default)]
+    pub prizes: Vec<ContestPrize>,
+    #[serde(default)]
+    pub schedule: Vec<ContestScheduleEntry>,
+    #[serde(default)]
+    pub faq: Vec<ContestFaqEntry>,
+    /// translations of the body keyed by locale, i.e. `en`
+    #[serde(default)]
+    pub localized: BTreeMap<String, LocalizedContestBody>,
+    /// unknown keys of a supported version, kept so they survive a round trip, bodies with a
+    /// version newer than `CONTEST_BODY_VERSION` are rejected instead
+    #[serde(flatten)]
+    #[ts(skip)]
+    pub extra: serde_json::Map<String, sqlx::types::JsonValue>,
 }
 
-impl From<sqlx::types::JsonValue> for ContestBody {
-    fn from(value: sqlx::types::JsonValue) -> Self {
-        let value = value.as_object().unwrap();
+impl Default for ContestBody {
+    fn default() -> Self {
         Self {
-            information: value["information"]
-                .as_str()
-                .unwrap_or_default()
-                .to_string(),
-            rules: value["rules"].as_str().unwrap_or_default().to_string(),
-            sponsor: value["sponsor"].as_str().unwrap_or_default().to_string(),
+            version: CONTEST_BODY_VERSION,
+            information: String::new(),
+            rules: String::new(),
+            sponsor: String::new(),
+            prizes: Vec::new(),
+            schedule: Vec::new(),
+            faq: Vec::new(),
+            localized: BTreeMap::new(),
+            extra: serde_json::Map::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+pub struct ContestPrize {
+    pub place: String,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
+#[ts(export)]
+pub struct ContestScheduleEntry {
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub time: DateTime<Utc>,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+pub struct ContestFaqEntry {
+    pub question: String,
+    pub answer: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
+#[ts(export)]
+pub struct LocalizedContestBody {
+    #[serde(default)]
+    pub information: String,
+    #[serde(default)]
+    pub rules: String,
+    #[serde(default)]
+    pub sponsor: String,
+}
+
+fn current_contest_body_version() -> u32 {
+    CONTEST_BODY_VERSION
+}
+
+#[derive(Debug)]
+pub enum ContestBodyError {
+    NotAnObject,
+    UnsupportedVersion(u64),
+    Malformed(serde_json::Error),
+}
+
+impl std::fmt::Display for ContestBodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContestBodyError::NotAnObject => write!(f, "contest body is not a json object"),
+            ContestBodyError::UnsupportedVersion(version) => {
+                write!(f, "unsupported contest body version {}", version)
+            },
+            ContestBodyError::Malformed(e) => write!(f, "malformed contest body: {}", e),
         }
     }
 }
 
+impl std::error::Error for ContestBodyError {}
+
+impl ContestBody {
+    /// Brings a body stored with an older shape up to `CONTEST_BODY_VERSION`.
+    ///
+    /// - version 1 (no `version` key): `information`, `rules` and `sponsor`, any of them could
+    ///   be missing or hold a non string value, those are read as empty strings.
+    fn migrate(
+        mut body: serde_json::Map<String, sqlx::types::JsonValue>,
+    ) -> Result<serde_json::Map<String, sqlx::types::JsonValue>, ContestBodyError> {
+        let mut version = match body.get("version") {
+            None => 1,
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| ContestBodyError::Malformed(serde::de::Error::custom(
+                    "version must be a positive integer",
+                )))?,
+        };
+        if version > CONTEST_BODY_VERSION as u64 || version == 0 {
+            return Err(ContestBodyError::UnsupportedVersion(version));
+        }
+        if version == 1 {
+            for key in ["information", "rules", "sponsor"] {
+                if !body.get(key).is_some_and(|value| value.is_string()) {
+                    body.insert(key.to_string(), String::new().into());
+                }
+            }
+            version = 2;
+        }
+        body.insert("version".to_string(), version.into());
+        Ok(body)
+    }
+}
+
+impl TryFrom<sqlx::types::JsonValue> for ContestBody {
+    type Error = ContestBodyError;
+
+    fn try_from(value: sqlx::types::JsonValue) -> Result<Self, Self::Error> {
+        let sqlx::types::JsonValue::Object(body) = value else {
+            return Err(ContestBodyError::NotAnObject);
+        };
+        let body = Self::migrate(body)?;
+        serde_json::from_value(body.into()).map_err(ContestBodyError::Malformed)
+    }
+}
+
+fn migrated_contest_body<'de, D>(deserializer: D) -> Result<ContestBody, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let body = sqlx::types::JsonValue::deserialize(deserializer)?;
+    ContestBody::try_from(body).map_err(serde::de::Error::custom)
+}
+
 #[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, TS, sqlx::Type)]
 #[ts(export)]
 #[sqlx(type_name = "contest_type", rename_all = "snake_case")]
@@ -146,3 +680,182 @@ pub enum ContestType {
     #[default]
     ICPC,
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    use crate::{
+        consts::{CONTEST_BODY_VERSION, MAX_PROBLEMS_PER_CONTEST},
+        contest::{
+            contest_problem_label, Contest, ContestBody, ContestForm, ContestGetResponse,
+            ContestProblem, ContestType, RegistrationPolicy,
+        },
+        problem::{ContestId, ProblemId},
+    };
+
+    fn form() -> ContestForm {
+        let start_date = Utc::now();
+        ContestForm {
+            name: "Concurso de prueba".to_string(),
+            body: ContestBody::default(),
+            start_date,
+            end_date: start_date + Duration::hours(5),
+            contest_type: ContestType::ICPC,
+            problems: ContestProblem::from_problem_ids(vec![ProblemId(1), ProblemId(2)]),
+            is_frozen: true,
+            frozen_time: 60 * 60,
+            registration_policy: RegistrationPolicy::Open,
+            registration_start: None,
+            registration_end: None,
+            registration_password: None,
+        }
+    }
+
+    fn contest() -> Contest {
+        let form = form();
+        Contest {
+            id: ContestId(1),
+            author: Uuid::new_v4(),
+            body: form.body,
+            name: form.name,
+            start_date: form.start_date,
+            end_date: form.end_date,
+            contest_type: form.contest_type,
+            problems: form.problems,
+            is_frozen: form.is_frozen,
+            frozen_time: form.frozen_time,
+            registration_policy: form.registration_policy,
+            registration_start: None,
+            registration_end: None,
+            registration_password_hash: None,
+        }
+    }
+
+    #[test]
+    fn contest_form_schedule_is_validated() {
+        assert!(form().validate().is_ok());
+        let fields = |form: ContestForm| {
+            let mut fields: Vec<_> =
+                form.validate().unwrap_err().field_errors().into_keys().collect();
+            fields.sort();
+            fields
+        };
+
+        let mut reversed = form();
+        reversed.end_date = reversed.start_date - Duration::hours(1);
+        assert_eq!(fields(reversed), vec!["end_date", "start_date"]);
+
+        let mut short = form();
+        short.end_date = short.start_date + Duration::minutes(1);
+        short.frozen_time = 0;
+        assert_eq!(fields(short), vec!["end_date"]);
+
+        let mut long_freeze = form();
+        long_freeze.frozen_time = 6 * 60 * 60;
+        assert_eq!(fields(long_freeze), vec!["frozen_time"]);
+    }
+
+    #[test]
+    fn contest_form_problems_are_validated() {
+        let mut repeated = form();
+        repeated.problems.push(ContestProblem::new(ProblemId(1), 2));
+        let errors = repeated.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("problems"));
+
+        let mut repeated_label = form();
+        repeated_label.problems[1].label = "A".to_string();
+        assert!(repeated_label.validate().is_err());
+
+        let mut bad_color = form();
+        bad_color.problems[0].color = Some("red".to_string());
+        assert!(bad_color.validate().is_err());
+
+        let mut too_many = form();
+        too_many.problems = ContestProblem::from_problem_ids(
+            (0..=MAX_PROBLEMS_PER_CONTEST as u32).map(ProblemId).collect(),
+        );
+        assert!(too_many.validate().is_err());
+    }
+
+    #[test]
+    fn contest_form_registration_is_validated() {
+        let mut late = form();
+        late.registration_end = Some(late.end_date + Duration::hours(1));
+        assert!(late.validate().unwrap_err().field_errors().contains_key("registration_end"));
+
+        let mut without_password = form();
+        without_password.registration_policy = RegistrationPolicy::Password;
+        let errors = without_password.validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("registration_password"));
+        without_password.registration_password = Some("acatlan".to_string());
+        assert!(without_password.validate().is_ok());
+    }
+
+    #[test]
+    fn contest_body_is_migrated_from_json() {
+        let legacy = json!({ "information": "info", "rules": 3 });
+        let body = ContestBody::try_from(legacy).unwrap();
+        assert_eq!(body.version, CONTEST_BODY_VERSION);
+        assert_eq!(body.information, "info");
+        assert_eq!(body.rules, "");
+
+        let newer = json!({ "version": 2, "information": "info", "awards": [] });
+        let body = ContestBody::try_from(newer).unwrap();
+        assert!(body.extra.contains_key("awards"));
+        assert!(serde_json::to_value(&body).unwrap().get("awards").is_some());
+
+        assert!(ContestBody::try_from(json!("info")).is_err());
+        assert!(ContestBody::try_from(json!({ "version": 99 })).is_err());
+
+        let mut stored = serde_json::to_value(contest()).unwrap();
+        stored["body"] = json!({ "information": "info", "rules": 3 });
+        let read: Contest = serde_json::from_value(stored.clone()).unwrap();
+        assert_eq!(read.body.version, CONTEST_BODY_VERSION);
+        assert_eq!(read.body.information, "info");
+        assert_eq!(read.body.rules, "");
+        stored["body"] = json!({ "version": 99 });
+        assert!(serde_json::from_value::<Contest>(stored).is_err());
+    }
+
+    #[test]
+    fn contests_with_problem_ids_are_read() {
+        let contest = contest();
+        let mut stored = serde_json::to_value(&contest).unwrap();
+        stored["problems"] = json!([1, 2]);
+
+        let read: Contest = serde_json::from_value(stored).unwrap();
+        assert_eq!(read.problems, contest.problems);
+        let read: Contest =
+            serde_json::from_value(serde_json::to_value(&contest).unwrap()).unwrap();
+        assert_eq!(read.problems, contest.problems);
+    }
+
+    #[test]
+    fn registration_password_hash_is_stored_but_not_sent() {
+        let mut contest = contest();
+        contest.registration_policy = RegistrationPolicy::Password;
+        contest.set_registration_password(Some("secreto")).unwrap();
+
+        let read: Contest =
+            serde_json::from_value(serde_json::to_value(&contest).unwrap()).unwrap();
+        assert!(read.check_registration_password(Some("secreto")));
+        assert!(!read.check_registration_password(Some("otro")));
+        assert!(!read.check_registration_password(None));
+
+        let response = serde_json::to_value(ContestGetResponse::from(&contest)).unwrap();
+        assert!(!response.to_string().contains(&read.registration_password_hash.unwrap()));
+    }
+
+    #[test]
+    fn contest_problem_labels_are_generated() {
+        assert_eq!(contest_problem_label(0), "A");
+        assert_eq!(contest_problem_label(25), "Z");
+        assert_eq!(contest_problem_label(26), "AA");
+        assert_eq!(contest_problem_label(27), "AB");
+        assert_eq!(contest_problem_label(26 * 27), "AAA");
+    }
+}
@@ -0,0 +1,166 @@
+//! Bounded diff between the expected output and the contestant output, shown to students when
+//! a sample test fails.
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::{
+    checker::{first_difference, tokens_match, truncate, Difference, LiteralCheckMode, Tokens},
+    consts::MAX_DIFF_MISMATCHES,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct OutputDiff {
+    /// at most one mismatch per line, in line order
+    pub mismatches: Vec<Difference>,
+    /// there were more mismatches than the ones reported
+    pub truncated: bool,
+    pub expected_lines: usize,
+    pub actual_lines: usize,
+}
+
+impl OutputDiff {
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Compares both outputs line by line reporting the first difference of every line, up to
+/// `MAX_DIFF_MISMATCHES` lines. Tokens are compared as `mode` does, only `Exact` reports
+/// differences in whitespace and `\r`.
+pub fn diff(expected: &[u8], actual: &[u8], mode: &LiteralCheckMode) -> OutputDiff {
+    let expected_lines = lines(expected, mode);
+    let actual_lines = lines(actual, mode);
+    let mut diff = OutputDiff {
+        expected_lines: expected_lines.len(),
+        actual_lines: actual_lines.len(),
+        ..Default::default()
+    };
+    for index in 0..expected_lines.len().max(actual_lines.len()) {
+        let mismatch = match (expected_lines.get(index), actual_lines.get(index)) {
+            (Some(expected), Some(actual)) => line_mismatch(index + 1, expected, actual, mode),
+            (expected, actual) => Some(Difference {
+                line: index + 1,
+                column: 1,
+                expected: expected.map(|line| truncate(line)),
+                actual: actual.map(|line| truncate(line)),
+            }),
+        };
+        if let Some(mismatch) = mismatch {
+            if diff.mismatches.len() == MAX_DIFF_MISMATCHES {
+                diff.truncated = true;
+                break;
+            }
+            diff.mismatches.push(mismatch);
+        }
+    }
+    diff
+}
+
+fn lines<'a>(output: &'a [u8], mode: &LiteralCheckMode) -> Vec<&'a [u8]> {
+    let lines = output.split(|byte| *byte == b'\n');
+    if *mode == LiteralCheckMode::Exact {
+        return lines.collect();
+    }
+    let mut lines: Vec<&[u8]> = lines.map(|line| line.trim_ascii_end()).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn line_mismatch(
+    line: usize,
+    expected: &[u8],
+    actual: &[u8],
+    mode: &LiteralCheckMode,
+) -> Option<Difference> {
+    if *mode == LiteralCheckMode::Exact {
+        let position = first_difference(expected, actual)?;
+        return Some(Difference {
+            line,
+            column: position + 1,
+            expected: Some(truncate(&expected[position..])),
+            actual: Some(truncate(&actual[position..])),
+        });
+    }
+    let mut expected_tokens = Tokens::new(expected);
+    let mut actual_tokens = Tokens::new(actual);
+    loop {
+        match (expected_tokens.next(), actual_tokens.next()) {
+            (None, None) => return None,
+            (Some(expected), Some(actual)) if tokens_match(expected.text, actual.text, mode) => {},
+            (expected_token, actual_token) => {
+                let column = match &actual_token {
+                    Some(token) => token.column,
+                    None => actual.len() + 1,
+                };
+                return Some(Difference {
+                    line,
+                    column,
+                    expected: expected_token.map(|token| truncate(token.text)),
+                    actual: actual_token.map(|token| truncate(token.text)),
+                });
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diff;
+    use crate::{checker::LiteralCheckMode, consts::MAX_DIFF_MISMATCHES};
+
+    #[test]
+    fn diff_reports_first_token_of_each_line() {
+        let diff = diff(b"1 2\n3 4\n5\n", b"1 2\r\n3 5\n", &LiteralCheckMode::Tokens);
+        assert_eq!(diff.mismatches.len(), 2);
+        assert_eq!((diff.mismatches[0].line, diff.mismatches[0].column), (2, 3));
+        assert_eq!(diff.mismatches[0].expected.as_deref(), Some("4"));
+        assert_eq!(diff.mismatches[1].actual, None);
+        assert!(!diff.truncated);
+    }
+
+    #[test]
+    fn diff_is_bounded() {
+        let expected = "0\n".repeat(MAX_DIFF_MISMATCHES * 2);
+        let actual = "1\n".repeat(MAX_DIFF_MISMATCHES * 2);
+        let diff = diff(expected.as_bytes(), actual.as_bytes(), &LiteralCheckMode::Tokens);
+        assert_eq!(diff.mismatches.len(), MAX_DIFF_MISMATCHES);
+        assert!(diff.truncated);
+    }
+
+    #[test]
+    fn diff_reports_whitespace_only_in_exact_mode() {
+        let expected = b"1 2\n3\n";
+        let actual = b"1  2\n3\n";
+        assert!(diff(expected, actual, &LiteralCheckMode::Tokens).is_empty());
+
+        let exact = diff(expected, actual, &LiteralCheckMode::Exact);
+        assert_eq!(exact.mismatches.len(), 1);
+        let mismatch = &exact.mismatches[0];
+        assert_eq!((mismatch.line, mismatch.column), (1, 3));
+        assert_eq!(mismatch.expected.as_deref(), Some("2"));
+        assert_eq!(mismatch.actual.as_deref(), Some(" 2"));
+
+        assert_eq!(diff(b"1\n", b"1\r\n", &LiteralCheckMode::Exact).mismatches.len(), 1);
+        assert_eq!(diff(b"1\n", b"1", &LiteralCheckMode::Exact).mismatches.len(), 1);
+    }
+
+    #[test]
+    fn diff_compares_tokens_as_the_checker_does() {
+        let expected = b"YES 0.5\n";
+        let actual = b"yes 0.5000001\n";
+        assert!(!diff(expected, actual, &LiteralCheckMode::Tokens).is_empty());
+        assert!(!diff(expected, actual, &LiteralCheckMode::CaseInsensitive).is_empty());
+
+        let case_only = diff(b"YES\n", b"yes\n", &LiteralCheckMode::CaseInsensitive);
+        assert!(case_only.is_empty());
+
+        let float = LiteralCheckMode::Float { absolute_epsilon: 1e-6, relative_epsilon: 1e-6 };
+        let mismatches = diff(b"YES 0.5 1\n", b"YES 0.5000001 2\n", &float).mismatches;
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].column, 15);
+        assert_eq!(mismatches[0].actual.as_deref(), Some("2"));
+    }
+}
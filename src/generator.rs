@@ -0,0 +1,200 @@
+//! Testlib generators and the script that runs them, so large tests can be rebuilt from a few
+//! lines instead of being the only copy of the data.
+//!
+//! A script has one test per line, `#` starts a comment:
+//!
+//! ```text
+//! gen 10 42 > 5
+//! gen-tree 100000 line > $
+//! ```
+//!
+//! `$` takes the smallest ordinal not used by the tests above it.
+use std::{collections::BTreeSet, fmt};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    consts::MAX_GENERATOR_SCRIPT_SIZE,
+    contest::Language,
+    problem::{validate_testlib_language, validate_testlib_source},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize, Validate)]
+#[ts(export)]
+pub struct GeneratorSource {
+    /// name used in the script
+    #[validate(length(min = 1, max = 32))]
+    pub name: String,
+    #[validate(custom = "validate_testlib_language")]
+    pub language: Language,
+    #[validate(custom = "validate_testlib_source")]
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, TS, Serialize, Deserialize, Validate)]
+#[ts(export)]
+#[validate(schema(function = "validate_generator_script", skip_on_field_errors = false))]
+pub struct GeneratorScript {
+    #[validate]
+    pub generators: Vec<GeneratorSource>,
+    #[validate(length(max = "MAX_GENERATOR_SCRIPT_SIZE"))]
+    pub script: String,
+}
+
+fn validate_generator_script(script: &GeneratorScript) -> Result<(), ValidationError> {
+    script.expand().map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("invalid_generator_script");
+        error.add_param("value".into(), &e.to_string());
+        error
+    })
+}
+
+/// A test case produced by running `generator` with `arguments`.
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct GeneratedTest {
+    pub ordinal: u32,
+    pub generator: String,
+    pub arguments: Vec<String>,
+    /// testlib seeds its random generator from the command line, this is the same value for
+    /// tools that need an explicit seed
+    #[ts(type = "number")]
+    pub seed: u64,
+}
+
+impl GeneratedTest {
+    pub fn new(ordinal: u32, generator: String, arguments: Vec<String>) -> Self {
+        let mut test = Self { ordinal, generator, arguments, seed: 0 };
+        let digest = Sha256::digest(test.command().as_bytes());
+        test.seed = u64::from_be_bytes(digest[..8].try_into().unwrap_or_default());
+        test
+    }
+
+    /// Parses a command without the redirection, e.g. `gen 10 42`.
+    pub fn from_command(ordinal: u32, command: &str) -> Option<Self> {
+        let mut words = command.split_whitespace().map(str::to_string);
+        Some(Self::new(ordinal, words.next()?, words.collect()))
+    }
+
+    pub fn command(&self) -> String {
+        std::iter::once(&self.generator)
+            .chain(&self.arguments)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratorScriptError {
+    Syntax { line: usize, message: &'static str },
+    UnknownGenerator { line: usize, name: String },
+    DuplicateTest { line: usize, ordinal: u32 },
+}
+
+impl fmt::Display for GeneratorScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GeneratorScriptError::Syntax { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            },
+            GeneratorScriptError::UnknownGenerator { line, name } => {
+                write!(f, "line {}: unknown generator {}", line, name)
+            },
+            GeneratorScriptError::DuplicateTest { line, ordinal } => {
+                write!(f, "line {}: test {} is generated twice", line, ordinal)
+            },
+        }
+    }
+}
+
+impl std::error::Error for GeneratorScriptError {}
+
+impl GeneratorScript {
+    /// Tests produced by the script, sorted by ordinal.
+    pub fn expand(&self) -> Result<Vec<GeneratedTest>, GeneratorScriptError> {
+        let mut ordinals = BTreeSet::new();
+        let mut tests = Vec::new();
+        for (index, line) in self.script.lines().enumerate() {
+            let line_number = index + 1;
+            let syntax = |message| GeneratorScriptError::Syntax { line: line_number, message };
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (command, target) =
+                line.rsplit_once('>').ok_or_else(|| syntax("missing `> <test>`"))?;
+            let ordinal = match target.trim() {
+                "$" => (1..).find(|ordinal| !ordinals.contains(ordinal)).unwrap_or_default(),
+                target => target
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|ordinal| *ordinal > 0)
+                    .ok_or_else(|| syntax("the test must be a positive number or `$`"))?,
+            };
+            let test = GeneratedTest::from_command(ordinal, command)
+                .ok_or_else(|| syntax("missing generator"))?;
+            if !self.generators.iter().any(|generator| generator.name == test.generator) {
+                return Err(GeneratorScriptError::UnknownGenerator {
+                    line: line_number,
+                    name: test.generator,
+                });
+            }
+            if !ordinals.insert(ordinal) {
+                return Err(GeneratorScriptError::DuplicateTest { line: line_number, ordinal });
+            }
+            tests.push(test);
+        }
+        tests.sort_by_key(|test| test.ordinal);
+        Ok(tests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GeneratorScript, GeneratorScriptError, GeneratorSource};
+    use crate::contest::Language;
+
+    fn script(script: &str) -> GeneratorScript {
+        GeneratorScript {
+            generators: vec![GeneratorSource {
+                name: "gen".to_string(),
+                language: Language::Cpp17,
+                source: "#include \"testlib.h\"".to_string(),
+            }],
+            script: script.to_string(),
+        }
+    }
+
+    #[test]
+    fn script_expands_into_seeded_tests() {
+        let tests = script("# large tests\ngen 10 42 > 3\n\ngen 5 > $\ngen 7 > $ # random")
+            .expand()
+            .unwrap();
+
+        assert_eq!(tests.iter().map(|test| test.ordinal).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(tests[2].command(), "gen 10 42");
+        assert_eq!(tests[0].arguments, vec!["5".to_string()]);
+        assert_ne!(tests[0].seed, tests[1].seed);
+        assert_eq!(tests[2].seed, script("gen 10 42 > 1").expand().unwrap()[0].seed);
+    }
+
+    #[test]
+    fn script_errors_point_to_the_line() {
+        assert_eq!(
+            script("gen 1 > 1\nother 2 > 2").expand(),
+            Err(GeneratorScriptError::UnknownGenerator { line: 2, name: "other".to_string() })
+        );
+        assert_eq!(
+            script("gen 1 > 1\ngen 2 > 1").expand(),
+            Err(GeneratorScriptError::DuplicateTest { line: 2, ordinal: 1 })
+        );
+        assert!(matches!(
+            script("gen 1").expand(),
+            Err(GeneratorScriptError::Syntax { line: 1, .. })
+        ));
+    }
+}
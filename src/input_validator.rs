@@ -0,0 +1,133 @@
+//! Testlib input validators, run over every test case input before a problem is published so
+//! broken test data never reaches contestants.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    checker::truncate_to,
+    consts::MAX_VALIDATOR_MESSAGE_SIZE,
+    contest::Language,
+    problem::{
+        validate_testlib_language, validate_testlib_source, CheckerCompileStatus, TestCaseInfo,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize, Validate)]
+#[ts(export)]
+pub struct Validator {
+    #[validate(custom = "validate_testlib_source")]
+    pub source: String,
+    #[validate(custom = "validate_testlib_language")]
+    pub language: Language,
+    #[serde(default)]
+    pub compile_status: CheckerCompileStatus,
+}
+
+impl Validator {
+    pub fn new(source: String, language: Language) -> Self {
+        Self { source, language, compile_status: CheckerCompileStatus::Pending }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct TestCaseValidation {
+    pub test_case_id: Uuid,
+    pub ordinal: u32,
+    pub passed: bool,
+    /// what the validator wrote to stderr, cut to `MAX_VALIDATOR_MESSAGE_SIZE` bytes
+    pub message: Option<String>,
+}
+
+impl TestCaseValidation {
+    /// Result of running the validator over `test_case`, testlib validators exit with 0 when
+    /// the input is valid and explain why it is not on stderr.
+    pub fn from_exit(test_case: &TestCaseInfo, exit_code: Option<i32>, stderr: &[u8]) -> Self {
+        let message = String::from_utf8_lossy(stderr).trim().to_string();
+        Self {
+            test_case_id: test_case.id,
+            ordinal: test_case.ordinal,
+            passed: exit_code == Some(0),
+            message: (!message.is_empty())
+                .then(|| truncate_to(message.as_bytes(), MAX_VALIDATOR_MESSAGE_SIZE)),
+        }
+    }
+}
+
+impl fmt::Display for TestCaseValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed { "valid" } else { "invalid" };
+        match &self.message {
+            Some(message) => write!(f, "test {} is {}: {}", self.ordinal, verdict, message),
+            None => write!(f, "test {} is {}", self.ordinal, verdict),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct TestCaseValidationReport {
+    /// in test case order
+    pub results: Vec<TestCaseValidation>,
+}
+
+impl TestCaseValidationReport {
+    pub fn push(&mut self, result: TestCaseValidation) {
+        let index = self.results.partition_point(|other| other.ordinal <= result.ordinal);
+        self.results.insert(index, result);
+    }
+
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|result| result.passed)
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &TestCaseValidation> {
+        self.results.iter().filter(|result| !result.passed)
+    }
+
+    /// One line per invalid test case, in the format `ImportedProblem::warnings` uses.
+    pub fn warnings(&self) -> Vec<String> {
+        self.failures().map(ToString::to_string).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{TestCaseValidation, TestCaseValidationReport};
+    use crate::problem::TestCaseInfo;
+
+    #[test]
+    fn report_lists_invalid_test_cases_in_order() {
+        let test_case =
+            |ordinal| TestCaseInfo { id: Uuid::new_v4(), ordinal, ..Default::default() };
+        let mut report = TestCaseValidationReport::default();
+        report.push(TestCaseValidation::from_exit(
+            &test_case(3),
+            Some(3),
+            b"FAIL Integer parameter [name=n] equals to 0, violates the range [1, 100]\n",
+        ));
+        report.push(TestCaseValidation::from_exit(&test_case(1), Some(0), b""));
+        report.push(TestCaseValidation::from_exit(&test_case(2), None, b""));
+
+        assert!(!report.passed());
+        assert_eq!(
+            report.results.iter().map(|result| result.ordinal).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            report.warnings(),
+            vec![
+                "test 2 is invalid".to_string(),
+                "test 3 is invalid: FAIL Integer parameter [name=n] equals to 0, violates the \
+                 range [1, 100]"
+                    .to_string(),
+            ]
+        );
+    }
+}
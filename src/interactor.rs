@@ -0,0 +1,216 @@
+//! Interactive problems, the contestant program talks with an interactor written by the problem
+//! setter instead of reading a fixed input.
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use validator::Validate;
+
+use crate::{
+    consts::{IDLENESS_LIMIT_FACTOR, MAX_INTERACTION_TRANSCRIPT_SIZE},
+    contest::Language,
+    problem::{validate_testlib_language, validate_testlib_source},
+    status::{Status, TestLibExitCodes},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum InteractionProtocol {
+    /// the interactor stdout is the contestant stdin and the other way around
+    #[default]
+    Stdio,
+    /// both programs communicate through named pipes passed as arguments
+    Files,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize, Validate)]
+#[ts(export)]
+pub struct InteractorSpec {
+    #[validate(custom = "validate_testlib_source")]
+    pub source: String,
+    #[validate(custom = "validate_testlib_language")]
+    pub language: Language,
+    #[validate(range(min = 1, max = 10))] // time limit in seconds
+    pub time_limit: u16,
+    /// queries the contestant can make per test case, `None` for no limit
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub query_limit: Option<u32>,
+    #[serde(default)]
+    pub protocol: InteractionProtocol,
+}
+
+/// How both programs finished a test case, as reported by the sandbox.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InteractionExit {
+    /// `None` when the interactor was killed
+    pub interactor_exit_code: Option<i32>,
+    /// `None` when the contestant program was killed
+    pub solution_exit_code: Option<i32>,
+    pub solution_cpu_time: Duration,
+    pub solution_wall_time: Duration,
+    pub queries: u32,
+}
+
+impl InteractorSpec {
+    /// Verdict of a test case, `time_limit` is the contestant time limit in seconds.
+    ///
+    /// The query and time limits are checked first, once the sandbox kills the contestant the
+    /// interactor reads end of file and answers with a wrong answer or format error. The
+    /// interactor verdict wins over the contestant exit code, a contestant that got a wrong
+    /// answer usually dies from a broken pipe once the interactor exits. A program that used
+    /// little cpu time but ran for `IDLENESS_LIMIT_FACTOR` times the time limit was waiting for
+    /// input it never got.
+    pub fn verdict(&self, exit: &InteractionExit, time_limit: u16) -> Status {
+        let time_limit = Duration::from_secs(time_limit as u64);
+        let interactor = exit.interactor_exit_code.map(TestLibExitCodes::try_from);
+        if self.query_limit.is_some_and(|limit| exit.queries > limit) {
+            return Status::WrongAnswer;
+        }
+        if exit.solution_cpu_time > time_limit {
+            return Status::TimeLimitExceeded;
+        }
+        if exit.solution_wall_time > time_limit * IDLENESS_LIMIT_FACTOR {
+            return Status::IdlenessLimitExceeded;
+        }
+        if let Some(Ok(TestLibExitCodes::WrongAnswer | TestLibExitCodes::FormatError)) = interactor
+        {
+            return Status::WrongAnswer;
+        }
+        if exit.solution_exit_code != Some(0) {
+            return Status::RuntimeError;
+        }
+        match interactor {
+            Some(Ok(TestLibExitCodes::Accepted)) => Status::Accepted,
+            Some(Ok(TestLibExitCodes::PartialExecution)) => Status::PartialPoints,
+            Some(Ok(_)) => Status::UnknownError("interactor failed".to_string()),
+            Some(Err(())) => Status::UnknownError(format!(
+                "interactor exited with code {}",
+                exit.interactor_exit_code.unwrap_or_default()
+            )),
+            None => Status::UnknownError("interactor was killed".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum InteractionDirection {
+    ToSolution,
+    ToInteractor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct TranscriptEntry {
+    pub direction: InteractionDirection,
+    pub data: String,
+}
+
+/// Messages exchanged during a test case, keeps at most `MAX_INTERACTION_TRANSCRIPT_SIZE`
+/// bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct InteractionTranscript {
+    pub entries: Vec<TranscriptEntry>,
+    /// messages were dropped or cut to fit the limit
+    pub truncated: bool,
+}
+
+impl InteractionTranscript {
+    /// Appends a message, consecutive messages in the same direction are merged.
+    pub fn push(&mut self, direction: InteractionDirection, data: &[u8]) {
+        let size: usize = self.entries.iter().map(|entry| entry.data.len()).sum();
+        let available = MAX_INTERACTION_TRANSCRIPT_SIZE.saturating_sub(size);
+        if data.len() > available {
+            self.truncated = true;
+        }
+        let data = &data[..data.len().min(available)];
+        if data.is_empty() {
+            return;
+        }
+        let data = String::from_utf8_lossy(data);
+        match self.entries.last_mut() {
+            Some(last) if last.direction == direction => last.data.push_str(&data),
+            _ => self.entries.push(TranscriptEntry { direction, data: data.into_owned() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{
+        InteractionDirection, InteractionExit, InteractionProtocol, InteractionTranscript,
+        InteractorSpec,
+    };
+    use crate::{consts::MAX_INTERACTION_TRANSCRIPT_SIZE, contest::Language, status::Status};
+
+    fn interactor() -> InteractorSpec {
+        InteractorSpec {
+            source: "#include \"testlib.h\"".to_string(),
+            language: Language::Cpp17,
+            time_limit: 2,
+            query_limit: Some(10),
+            protocol: InteractionProtocol::Stdio,
+        }
+    }
+
+    fn exit(interactor: i32, solution: i32, cpu: u64, wall: u64) -> InteractionExit {
+        InteractionExit {
+            interactor_exit_code: Some(interactor),
+            solution_exit_code: Some(solution),
+            solution_cpu_time: Duration::from_millis(cpu),
+            solution_wall_time: Duration::from_millis(wall),
+            queries: 3,
+        }
+    }
+
+    #[test]
+    fn interactor_exit_codes_map_to_verdicts() {
+        let interactor = interactor();
+        assert_eq!(interactor.verdict(&exit(0, 0, 500, 600), 1), Status::Accepted);
+        assert_eq!(interactor.verdict(&exit(1, 1, 500, 600), 1), Status::WrongAnswer);
+        assert_eq!(interactor.verdict(&exit(0, 0, 1500, 1600), 1), Status::TimeLimitExceeded);
+        assert_eq!(interactor.verdict(&exit(0, 0, 100, 5000), 1), Status::IdlenessLimitExceeded);
+        assert_eq!(interactor.verdict(&exit(0, 139, 100, 200), 1), Status::RuntimeError);
+        assert!(matches!(interactor.verdict(&exit(3, 0, 100, 200), 1), Status::UnknownError(_)));
+
+        let mut queries = exit(0, 0, 100, 200);
+        queries.queries = 11;
+        assert_eq!(interactor.verdict(&queries, 1), Status::WrongAnswer);
+    }
+
+    #[test]
+    fn killed_solutions_are_not_blamed_on_the_interactor() {
+        let interactor = interactor();
+        let mut time_limit = exit(2, 0, 1500, 1600);
+        time_limit.solution_exit_code = None;
+        assert_eq!(interactor.verdict(&time_limit, 1), Status::TimeLimitExceeded);
+
+        let mut idle = exit(2, 0, 100, 5000);
+        idle.solution_exit_code = None;
+        assert_eq!(interactor.verdict(&idle, 1), Status::IdlenessLimitExceeded);
+
+        assert_eq!(interactor.verdict(&exit(2, 0, 100, 200), 1), Status::WrongAnswer);
+    }
+
+    #[test]
+    fn transcript_is_bounded() {
+        let mut transcript = InteractionTranscript::default();
+        transcript.push(InteractionDirection::ToSolution, b"5\n");
+        transcript.push(InteractionDirection::ToInteractor, b"? 3\n");
+        transcript.push(InteractionDirection::ToInteractor, b"? 4\n");
+        assert_eq!(transcript.entries.len(), 2);
+        assert_eq!(transcript.entries[1].data, "? 3\n? 4\n");
+
+        transcript
+            .push(InteractionDirection::ToSolution, &vec![b'1'; MAX_INTERACTION_TRANSCRIPT_SIZE]);
+        assert!(transcript.truncated);
+        let size: usize = transcript.entries.iter().map(|entry| entry.data.len()).sum();
+        assert_eq!(size, MAX_INTERACTION_TRANSCRIPT_SIZE);
+    }
+}
@@ -1,12 +1,27 @@
+pub mod checker;
+pub mod clarification;
 pub mod consts;
+pub mod diff;
+pub mod generator;
+pub mod input_validator;
 pub mod contest;
+pub mod interactor;
 pub mod macros;
+pub mod package;
+pub mod participation;
 pub mod problem;
+pub mod problemset;
 pub mod serde;
+pub mod solution;
 pub mod status;
+pub mod storage;
 pub mod submit;
+pub mod team;
 pub mod utils;
+pub mod registration;
 pub mod relations;
+pub mod revision;
+pub mod render;
 pub mod user;
 pub use uuid::Uuid;
 
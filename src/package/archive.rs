@@ -0,0 +1,476 @@
+//! Native problem archive, a zip holding everything needed to recreate a problem on another
+//! instance:
+//!
+//! ```text
+//! manifest.json       format version, checksums and test case order
+//! problem.json        `Problem` without its body and checker source
+//! statement.json      `ProblemBody`
+//! checker.txt         checker source, only when the problem has one
+//! tests/<uuid>.in
+//! tests/<uuid>.out
+//! ```
+use std::{
+    io::{Read, Seek, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{
+    consts::{
+        MAX_ARCHIVE_METADATA_SIZE_IN_BYTES, MAX_TESCASE_FILE_SIZE_IN_BYTES, PROBLEM_ARCHIVE_VERSION,
+    },
+    contest::Language,
+    problem::{Checker, Problem, ProblemBody, TestCaseInfo},
+    storage::TestCaseStore,
+};
+
+const MANIFEST: &str = "manifest.json";
+const PROBLEM: &str = "problem.json";
+const STATEMENT: &str = "statement.json";
+const CHECKER: &str = "checker.txt";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    /// version the archive was written with
+    pub version: u32,
+    pub files: Vec<ArchiveFile>,
+    /// paths are relative to the root of the archive
+    pub test_cases: Vec<TestCaseInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ArchiveFile {
+    pub path: String,
+    /// hex encoded sha256 of the file contents
+    pub sha256: String,
+    pub size: u64,
+}
+
+impl ArchiveManifest {
+    /// Parses a manifest written by this or an older version.
+    ///
+    /// - version 1: `test_cases` held only the ids, the files are at `tests/<uuid>.in` and
+    ///   `tests/<uuid>.out` and the position gives the ordinal. Archives written while test case
+    ///   metadata was added already hold `TestCaseInfo`s under version 1, those are kept.
+    fn parse(contents: &[u8]) -> Result<Self> {
+        let malformed = "problem archive manifest is malformed";
+        let mut manifest: serde_json::Value =
+            serde_json::from_slice(contents).context(malformed)?;
+        let version = manifest
+            .get("version")
+            .and_then(|version| version.as_u64())
+            .ok_or_else(|| anyhow!("problem archive manifest has no version"))?;
+        if version > PROBLEM_ARCHIVE_VERSION as u64 || version == 0 {
+            return Err(anyhow!(
+                "problem archive version {} is not supported",
+                version
+            ));
+        }
+        if version == 1 {
+            let test_cases = manifest
+                .get_mut("test_cases")
+                .and_then(|test_cases| test_cases.as_array_mut());
+            for (index, test_case) in test_cases.into_iter().flatten().enumerate() {
+                let Some(id) = test_case.as_str().and_then(|id| Uuid::parse_str(id).ok()) else {
+                    continue;
+                };
+                *test_case = serde_json::to_value(TestCaseInfo {
+                    stdin_path: input_path(&id),
+                    stdout_path: output_path(&id),
+                    id,
+                    ordinal: index as u32 + 1,
+                    ..Default::default()
+                })?;
+            }
+        }
+        serde_json::from_value(manifest).context(malformed)
+    }
+}
+
+impl ArchiveFile {
+    fn new(path: String, contents: &[u8]) -> Self {
+        Self {
+            path,
+            sha256: hex::encode(Sha256::digest(contents)),
+            size: contents.len() as u64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedTestCase {
+    /// paths are relative to the root of the archive
+    pub info: TestCaseInfo,
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProblemArchive {
+    pub problem: Problem,
+    /// in the same order as `problem.test_cases`
+    pub test_cases: Vec<ArchivedTestCase>,
+}
+
+impl ProblemArchive {
+    /// Reads the contents of every test case of `problem` from `test_cases`.
+    pub fn new(problem: Problem, test_cases: &[TestCaseInfo]) -> Result<Self> {
+        let test_cases = problem
+            .test_cases
+            .iter()
+            .map(|id| {
+                let info = test_cases
+                    .iter()
+                    .find(|info| info.id == *id)
+                    .ok_or_else(|| anyhow!("test case {} has no files", id))?;
+                let mut archived = info.clone();
+                archived.stdin_path = input_path(id);
+                archived.stdout_path = output_path(id);
+                Ok(ArchivedTestCase {
+                    info: archived,
+                    input: std::fs::read(&info.stdin_path)
+                        .with_context(|| format!("unable to read {}", info.stdin_path))?,
+                    output: std::fs::read(&info.stdout_path)
+                        .with_context(|| format!("unable to read {}", info.stdout_path))?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            problem,
+            test_cases,
+        })
+    }
+
+    pub fn pack<W: Write + Seek>(&self, writer: W) -> Result<()> {
+        let mut files = Vec::new();
+
+        let mut metadata = self.problem.clone();
+        metadata.body = ProblemBody::default();
+        if let Some(checker) = &mut metadata.checker {
+            checker.source = None;
+        }
+        files.push((PROBLEM.to_string(), serde_json::to_vec_pretty(&metadata)?));
+        files.push((
+            STATEMENT.to_string(),
+            serde_json::to_vec_pretty(&self.problem.body)?,
+        ));
+        if let Some(source) = self
+            .problem
+            .checker
+            .as_ref()
+            .and_then(|checker| checker.source.as_ref())
+        {
+            files.push((CHECKER.to_string(), source.clone().into_bytes()));
+        }
+        for test_case in &self.test_cases {
+            files.push((test_case.info.stdin_path.clone(), test_case.input.clone()));
+            files.push((test_case.info.stdout_path.clone(), test_case.output.clone()));
+        }
+
+        let manifest = ArchiveManifest {
+            version: PROBLEM_ARCHIVE_VERSION,
+            files: files
+                .iter()
+                .map(|(path, contents)| ArchiveFile::new(path.clone(), contents))
+                .collect(),
+            test_cases: self
+                .test_cases
+                .iter()
+                .map(|test_case| test_case.info.clone())
+                .collect(),
+        };
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(writer);
+        zip.start_file(MANIFEST, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        for (path, contents) in files {
+            zip.start_file(path, options)?;
+            zip.write_all(&contents)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+
+    /// Reads an archive written by `pack`, failing if any file is missing or does not match
+    /// the checksum in the manifest.
+    pub fn unpack<R: Read + Seek>(reader: R) -> Result<Self> {
+        let mut zip = ZipArchive::new(reader).context("problem archive is not a zip")?;
+        let manifest = ArchiveManifest::parse(&read_entry(
+            &mut zip,
+            MANIFEST,
+            MAX_ARCHIVE_METADATA_SIZE_IN_BYTES,
+        )?)?;
+
+        let mut read_verified = |path: &str, max: u64| -> Result<Vec<u8>> {
+            let expected = manifest
+                .files
+                .iter()
+                .find(|file| file.path == path)
+                .ok_or_else(|| anyhow!("{} is not listed in the manifest", path))?;
+            let contents = read_entry(&mut zip, path, max)?;
+            if ArchiveFile::new(path.to_string(), &contents) != *expected {
+                return Err(anyhow!("{} does not match its checksum", path));
+            }
+            Ok(contents)
+        };
+
+        let mut problem: Problem = parse(
+            &read_verified(PROBLEM, MAX_ARCHIVE_METADATA_SIZE_IN_BYTES)?,
+            PROBLEM,
+        )?;
+        problem.body = parse(
+            &read_verified(STATEMENT, MAX_ARCHIVE_METADATA_SIZE_IN_BYTES)?,
+            STATEMENT,
+        )?;
+        if manifest.files.iter().any(|file| file.path == CHECKER) {
+            let source =
+                String::from_utf8(read_verified(CHECKER, MAX_ARCHIVE_METADATA_SIZE_IN_BYTES)?)
+                    .context("checker source is not utf-8")?;
+            match &mut problem.checker {
+                Some(checker) => checker.source = Some(source),
+                // version 1 archives only kept the source of testlib checkers
+                None => problem.checker = Some(Checker::testlib(source, Language::Cpp17)),
+            }
+        }
+        let max = MAX_TESCASE_FILE_SIZE_IN_BYTES as u64;
+        let test_cases = manifest
+            .test_cases
+            .iter()
+            .map(|info| {
+                Ok(ArchivedTestCase {
+                    info: info.clone(),
+                    input: read_verified(&info.stdin_path, max)?,
+                    output: read_verified(&info.stdout_path, max)?,
+                })
+            })
+            .collect::<Result<_>>()?;
+        problem.test_cases = manifest.test_cases.iter().map(|info| info.id).collect();
+
+        Ok(Self {
+            problem,
+            test_cases,
+        })
+    }
+
+    /// Stores the test cases in `store`, keeping their ordinals, groups and flags.
+    pub fn store_test_cases(&self, store: &impl TestCaseStore) -> Result<Vec<TestCaseInfo>> {
+        self.test_cases
+            .iter()
+            .map(|test_case| {
+                let stored = store.put_test_case(
+                    self.problem.id.clone(),
+                    &test_case.input,
+                    &test_case.output,
+                )?;
+                Ok(TestCaseInfo {
+                    stdin_path: stored.stdin_path,
+                    stdout_path: stored.stdout_path,
+                    stdin_metadata: stored.stdin_metadata,
+                    stdout_metadata: stored.stdout_metadata,
+                    problem_id: self.problem.id.clone(),
+                    ..test_case.info.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// Stores the test cases under `directory` as `<uuid>.in` and `<uuid>.out`.
+    pub fn write_test_cases(&self, directory: &Path) -> Result<Vec<TestCaseInfo>> {
+        std::fs::create_dir_all(directory)?;
+        self.test_cases
+            .iter()
+            .map(|test_case| {
+                let stdin_path = directory.join(format!("{}.in", test_case.info.id));
+                let stdout_path = directory.join(format!("{}.out", test_case.info.id));
+                std::fs::write(&stdin_path, &test_case.input)?;
+                std::fs::write(&stdout_path, &test_case.output)?;
+                Ok(TestCaseInfo {
+                    stdin_path: stdin_path.to_string_lossy().into_owned(),
+                    stdout_path: stdout_path.to_string_lossy().into_owned(),
+                    problem_id: self.problem.id.clone(),
+                    ..test_case.info.clone()
+                })
+            })
+            .collect()
+    }
+}
+
+fn input_path(id: &Uuid) -> String {
+    format!("tests/{}.in", id)
+}
+
+fn output_path(id: &Uuid) -> String {
+    format!("tests/{}.out", id)
+}
+
+/// Reads at most `max` bytes of `path`, the size in the zip header is only trusted to fail
+/// early since a crafted archive can understate it.
+fn read_entry<R: Read + Seek>(zip: &mut ZipArchive<R>, path: &str, max: u64) -> Result<Vec<u8>> {
+    let entry = zip
+        .by_name(path)
+        .with_context(|| format!("{} is missing from the problem archive", path))?;
+    let too_large = || anyhow!("{} exceeds the limit of {} bytes", path, max);
+    if entry.size() > max {
+        return Err(too_large());
+    }
+    let mut contents = Vec::with_capacity(entry.size() as usize);
+    entry.take(max + 1).read_to_end(&mut contents)?;
+    if contents.len() as u64 > max {
+        return Err(too_large());
+    }
+    Ok(contents)
+}
+
+fn parse<T: DeserializeOwned>(contents: &[u8], path: &str) -> Result<T> {
+    serde_json::from_slice(contents).with_context(|| format!("{} is malformed", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use uuid::Uuid;
+    use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+    use super::{ArchiveFile, ArchivedTestCase, ProblemArchive};
+    use crate::{
+        consts::MAX_ARCHIVE_METADATA_SIZE_IN_BYTES,
+        contest::Language,
+        problem::{Checker, Problem, TestCaseInfo},
+    };
+
+    fn archive() -> ProblemArchive {
+        let id = Uuid::new_v4();
+        let mut problem = Problem::default();
+        problem.body.name = "Suma".to_string();
+        problem.checker = Some(Checker::testlib(
+            "#include \"testlib.h\"".to_string(),
+            Language::Cpp17,
+        ));
+        problem.test_cases = vec![id];
+        ProblemArchive {
+            problem,
+            test_cases: vec![ArchivedTestCase {
+                info: TestCaseInfo {
+                    stdin_path: format!("tests/{}.in", id),
+                    stdout_path: format!("tests/{}.out", id),
+                    id,
+                    ordinal: 1,
+                    is_sample: true,
+                    ..Default::default()
+                },
+                input: b"1 2\n".to_vec(),
+                output: b"3\n".to_vec(),
+            }],
+        }
+    }
+
+    #[test]
+    fn problem_archive_round_trips() {
+        let archive = archive();
+        let mut zip = Cursor::new(Vec::new());
+        archive.pack(&mut zip).unwrap();
+        let unpacked = ProblemArchive::unpack(Cursor::new(zip.into_inner())).unwrap();
+
+        assert_eq!(unpacked.problem.body.name, "Suma");
+        let checker = unpacked.problem.checker.unwrap();
+        assert_eq!(checker.source, archive.problem.checker.unwrap().source);
+        assert_eq!(checker.language, Some(Language::Cpp17));
+        assert_eq!(unpacked.problem.test_cases, archive.problem.test_cases);
+        assert_eq!(unpacked.test_cases, archive.test_cases);
+    }
+
+    #[test]
+    fn tampered_problem_archive_is_rejected() {
+        let mut packed = Cursor::new(Vec::new());
+        archive().pack(&mut packed).unwrap();
+        let mut original = ZipArchive::new(Cursor::new(packed.into_inner())).unwrap();
+
+        let mut tampered = ZipWriter::new(Cursor::new(Vec::new()));
+        for index in 0..original.len() {
+            let mut entry = original.by_index(index).unwrap();
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents).unwrap();
+            if entry.name().ends_with(".in") {
+                contents = b"2 2\n".to_vec();
+            }
+            tampered
+                .start_file(entry.name(), SimpleFileOptions::default())
+                .unwrap();
+            tampered.write_all(&contents).unwrap();
+        }
+        let tampered = tampered.finish().unwrap().into_inner();
+
+        assert!(ProblemArchive::unpack(Cursor::new(tampered)).is_err());
+    }
+
+    #[test]
+    fn oversized_archive_entry_is_rejected() {
+        let mut bomb = ZipWriter::new(Cursor::new(Vec::new()));
+        bomb.start_file("manifest.json", SimpleFileOptions::default())
+            .unwrap();
+        let spaces = vec![b' '; MAX_ARCHIVE_METADATA_SIZE_IN_BYTES as usize + 1];
+        bomb.write_all(&spaces).unwrap();
+        let bomb = bomb.finish().unwrap().into_inner();
+
+        assert!(bomb.len() < 64 * 1024);
+        let error = ProblemArchive::unpack(Cursor::new(bomb)).unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit"));
+    }
+
+    #[test]
+    fn version_1_problem_archive_is_migrated() {
+        let id = Uuid::new_v4();
+        let mut problem = Problem::default();
+        problem.body.name = "Suma".to_string();
+        let files = [
+            (
+                "problem.json".to_string(),
+                serde_json::to_vec(&problem).unwrap(),
+            ),
+            (
+                "statement.json".to_string(),
+                serde_json::to_vec(&problem.body).unwrap(),
+            ),
+            (
+                "checker.txt".to_string(),
+                b"#include \"testlib.h\"".to_vec(),
+            ),
+            (format!("tests/{}.in", id), b"1 2\n".to_vec()),
+            (format!("tests/{}.out", id), b"3\n".to_vec()),
+        ];
+        let manifest = serde_json::json!({
+            "version": 1,
+            "files": files
+                .iter()
+                .map(|(path, contents)| ArchiveFile::new(path.clone(), contents))
+                .collect::<Vec<_>>(),
+            "test_cases": [id],
+        });
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        zip.start_file("manifest.json", SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(&serde_json::to_vec(&manifest).unwrap())
+            .unwrap();
+        for (path, contents) in &files {
+            zip.start_file(path.as_str(), SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        let zip = zip.finish().unwrap().into_inner();
+        let unpacked = ProblemArchive::unpack(Cursor::new(zip)).unwrap();
+
+        assert_eq!(unpacked.problem.test_cases, vec![id]);
+        assert_eq!(unpacked.test_cases[0].info.ordinal, 1);
+        assert_eq!(unpacked.test_cases[0].input, b"1 2\n");
+        let checker = unpacked.problem.checker.unwrap();
+        assert_eq!(checker.source.as_deref(), Some("#include \"testlib.h\""));
+    }
+}
@@ -0,0 +1,821 @@
+//! Import and export of the Kattis [problem package format](https://www.kattis.com/problem-package-format/)
+//! used by most ICPC regionals.
+//!
+//! Both the legacy layout (`problem_statement/problem.<lang>.tex`, `.timelimit`) and the newer
+//! one (`statement/problem.<lang>.md`, `limits.time_limit`) are read, packages are always written
+//! with `limits.time_limit` set.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    locale_from_language, memory_limit_from_bytes, package_path, read_to_string,
+    time_limit_from_millis, ImportedProblem,
+};
+use crate::{
+    checker::LiteralCheckMode,
+    consts::DEFAULT_LOCALE,
+    contest::Language,
+    input_validator::Validator,
+    interactor::{InteractionProtocol, InteractorSpec},
+    problem::{
+        order_test_cases, Checker, CheckerCompileStatus, CheckerKind, Locale, Problem,
+        ProblemStatement, StatementFormat, TestCaseInfo, ValidationType,
+    },
+    solution::{ExpectedVerdict, ProblemSolution},
+    storage::metadata_of,
+};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ProblemYaml {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<serde_yaml::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(default = "default_validation")]
+    validation: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    validator_flags: Option<String>,
+    #[serde(default)]
+    limits: Limits,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Limits {
+    /// seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time_limit: Option<f64>,
+    /// megabytes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memory: Option<u64>,
+}
+
+fn default_validation() -> String {
+    "default".to_string()
+}
+
+/// Statement layouts, the section headings split the statement into `ProblemStatement` fields.
+struct StatementLayout {
+    directory: &'static str,
+    extension: &'static str,
+    format: StatementFormat,
+    input_heading: &'static str,
+    output_heading: &'static str,
+    notes_heading: &'static str,
+}
+
+const STATEMENT_LAYOUTS: [StatementLayout; 2] = [
+    StatementLayout {
+        directory: "statement",
+        extension: "md",
+        format: StatementFormat::Markdown,
+        input_heading: "## Input",
+        output_heading: "## Output",
+        notes_heading: "## Notes",
+    },
+    StatementLayout {
+        directory: "problem_statement",
+        extension: "tex",
+        format: StatementFormat::Latex,
+        input_heading: "\\section*{Input}",
+        output_heading: "\\section*{Output}",
+        notes_heading: "\\section*{Notes}",
+    },
+];
+
+pub fn import(package: &Path, submitted_by: Uuid) -> Result<ImportedProblem> {
+    let yaml = read_to_string(&package.join("problem.yaml"))?;
+    let metadata: ProblemYaml = serde_yaml::from_str(&yaml).context("problem.yaml is malformed")?;
+
+    let mut imported = ImportedProblem::default();
+    imported.problem.submitted_by = submitted_by;
+    imported.problem.created_at = chrono::Utc::now();
+    imported.problem.source = metadata.source.clone();
+
+    import_statements(package, &metadata, &mut imported)?;
+    import_limits(package, &metadata, &mut imported)?;
+    import_validation(package, &metadata, &mut imported)?;
+    if let Some(source) = ["input_validators", "input_format_validators"]
+        .iter()
+        .find_map(|directory| cpp_source_in(&package.join(directory)))
+    {
+        imported.problem.validator =
+            Some(Validator::new(read_to_string(&source)?, Language::Cpp17));
+        imported
+            .warn("the input validator was imported as a testlib validator, review its exit codes");
+    }
+
+    import_solutions(&package.join("submissions"), &mut imported)?;
+
+    let samples = test_cases_in(&package.join("data").join("sample"), &mut imported)?;
+    let secret = test_cases_in(&package.join("data").join("secret"), &mut imported)?;
+    let test_cases = samples
+        .into_iter()
+        .map(|test_case| (true, test_case))
+        .chain(secret.into_iter().map(|test_case| (false, test_case)));
+    for (index, (is_sample, (group, stdin_path, stdout_path))) in test_cases.enumerate() {
+        let test_case = TestCaseInfo {
+            stdin_metadata: Some(metadata_of(&stdin_path)?),
+            stdout_metadata: Some(metadata_of(&stdout_path)?),
+            stdin_path: stdin_path.to_string_lossy().into_owned(),
+            stdout_path: stdout_path.to_string_lossy().into_owned(),
+            problem_id: imported.problem.id.clone(),
+            id: Uuid::new_v4(),
+            ordinal: index as u32 + 1,
+            group,
+            is_sample,
+            points: None,
+            description: None,
+            generated_by: None,
+        };
+        imported.problem.test_cases.push(test_case.id);
+        imported.test_cases.push(test_case);
+    }
+    imported.problem.body.sync_examples(&imported.test_cases)?;
+    Ok(imported)
+}
+
+/// Writes `problem` as a Kattis package into `package`, returns the features that could not be
+/// exported.
+pub fn export(
+    problem: &Problem,
+    test_cases: &[TestCaseInfo],
+    package: &Path,
+) -> Result<Vec<String>> {
+    let validation = match problem.validation {
+        ValidationType::LiteralChecker => "default",
+        ValidationType::TestlibChecker => "custom",
+        ValidationType::Interactive => "custom interactive",
+        ValidationType::OutputOnly | ValidationType::RunTwice => {
+            return Err(anyhow!(
+                "{} problems can not be exported to kattis",
+                problem.validation
+            ));
+        },
+    };
+
+    let mut warnings = Vec::new();
+    let body = &problem.body;
+    let layout = STATEMENT_LAYOUTS
+        .iter()
+        .find(|layout| layout.format == body.format)
+        .unwrap_or_else(|| {
+            warnings.push(format!(
+                "{:?} statements were exported as markdown",
+                body.format
+            ));
+            &STATEMENT_LAYOUTS[0]
+        });
+
+    let statements = package.join(layout.directory);
+    fs::create_dir_all(&statements)?;
+    let mut names = serde_yaml::Mapping::new();
+    let localized = std::iter::once((&body.locale, body.statement())).chain(
+        body.translations
+            .iter()
+            .map(|(locale, statement)| (locale, statement.clone())),
+    );
+    for (locale, statement) in localized {
+        names.insert(locale.0.clone().into(), statement.name.clone().into());
+        let name = format!("problem.{}.{}", locale, layout.extension);
+        fs::write(
+            package_path(&statements, &name)?,
+            write_statement(&statement, layout),
+        )?;
+    }
+
+    let metadata = ProblemYaml {
+        name: Some(serde_yaml::Value::Mapping(names)),
+        source: problem.source.clone(),
+        author: None,
+        validation: validation.to_string(),
+        validator_flags: problem
+            .checker
+            .as_ref()
+            .and_then(|checker| match &checker.kind {
+                CheckerKind::Literal(mode) => validator_flags(mode),
+                _ => None,
+            }),
+        limits: Limits {
+            time_limit: Some(problem.time_limit as f64),
+            memory: Some(problem.memory_limit as u64),
+        },
+    };
+    fs::write(
+        package.join("problem.yaml"),
+        serde_yaml::to_string(&metadata)?,
+    )?;
+
+    if let Some(Checker {
+        kind,
+        source: Some(source),
+        ..
+    }) = &problem.checker
+    {
+        let validators = package.join("output_validators").join("checker");
+        fs::create_dir_all(&validators)?;
+        fs::write(validators.join("checker.cpp"), source)?;
+        if *kind != CheckerKind::Kattis {
+            warnings.push(
+                "the checker was exported as is, Kattis output validators answer with exit codes 42/43"
+                    .to_string(),
+            );
+        }
+    }
+    if let Some(interactor) = &problem.interactor {
+        let validators = package.join("output_validators").join("interactor");
+        fs::create_dir_all(&validators)?;
+        fs::write(validators.join("interactor.cpp"), &interactor.source)?;
+        warnings.push(
+            "the interactor was exported as is, Kattis interactors answer with exit codes 42/43"
+                .to_string(),
+        );
+    }
+
+    let mut test_cases = test_cases.to_vec();
+    order_test_cases(&mut test_cases);
+    if !test_cases.iter().any(|test_case| test_case.is_sample) {
+        let samples = package.join("data").join("sample");
+        fs::create_dir_all(&samples)?;
+        for (index, example) in body.examples.iter().enumerate() {
+            fs::write(samples.join(format!("{:03}.in", index + 1)), &example.input)?;
+            fs::write(
+                samples.join(format!("{:03}.ans", index + 1)),
+                &example.output,
+            )?;
+        }
+    }
+    for test_case in &test_cases {
+        let kind = if test_case.is_sample {
+            "sample"
+        } else {
+            "secret"
+        };
+        let mut directory = package.join("data").join(kind);
+        if let Some(group) = &test_case.group {
+            directory = package_path(&directory, group)?;
+        }
+        fs::create_dir_all(&directory)?;
+        let name = format!("{:03}", test_case.ordinal);
+        fs::copy(
+            &test_case.stdin_path,
+            directory.join(format!("{}.in", name)),
+        )
+        .with_context(|| format!("unable to copy {}", test_case.stdin_path))?;
+        fs::copy(
+            &test_case.stdout_path,
+            directory.join(format!("{}.ans", name)),
+        )
+        .with_context(|| format!("unable to copy {}", test_case.stdout_path))?;
+    }
+    Ok(warnings)
+}
+
+fn import_statements(
+    package: &Path,
+    metadata: &ProblemYaml,
+    imported: &mut ImportedProblem,
+) -> Result<()> {
+    let Some((layout, mut statements)) = STATEMENT_LAYOUTS.iter().find_map(|layout| {
+        let statements = find_statements(&package.join(layout.directory), layout.extension);
+        (!statements.is_empty()).then_some((layout, statements))
+    }) else {
+        imported.warn("package has no statement");
+        return Ok(());
+    };
+    statements.sort_by_key(|(locale, _)| locale != DEFAULT_LOCALE);
+
+    let body = &mut imported.problem.body;
+    body.format = layout.format.clone();
+    for (index, (locale, path)) in statements.into_iter().enumerate() {
+        let mut statement = read_statement(&read_to_string(&path)?, layout);
+        if let Some(name) = statement_name(metadata, &locale) {
+            statement.name = name;
+        }
+        if index == 0 {
+            body.locale = Locale(locale);
+            body.name = statement.name;
+            body.input = statement.input;
+            body.output = statement.output;
+            body.problem = statement.problem;
+            body.note = statement.note;
+        } else {
+            body.translations.insert(Locale(locale), statement);
+        }
+    }
+    Ok(())
+}
+
+fn import_limits(
+    package: &Path,
+    metadata: &ProblemYaml,
+    imported: &mut ImportedProblem,
+) -> Result<()> {
+    let time_limit = match metadata.limits.time_limit {
+        Some(seconds) => Some(seconds),
+        None => {
+            let timelimit = package.join(".timelimit");
+            if timelimit.is_file() {
+                Some(read_to_string(&timelimit)?.trim().parse()?)
+            } else {
+                None
+            }
+        },
+    };
+    imported.problem.time_limit = match time_limit {
+        Some(seconds) => time_limit_from_millis((seconds * 1000.0).ceil() as u64, imported),
+        None => {
+            imported.warn("package has no time limit, 1s was used");
+            1
+        },
+    };
+    let memory = metadata.limits.memory.unwrap_or(2048);
+    let bytes = memory
+        .checked_mul(1024 * 1024)
+        .ok_or_else(|| anyhow!("memory limit of {}MB is too large", memory))?;
+    imported.problem.memory_limit = memory_limit_from_bytes(bytes, imported);
+    Ok(())
+}
+
+fn import_validation(
+    package: &Path,
+    metadata: &ProblemYaml,
+    imported: &mut ImportedProblem,
+) -> Result<()> {
+    let mut modes = metadata.validation.split_whitespace();
+    match (modes.next(), modes.next()) {
+        (Some("default") | None, _) => {
+            let mode = literal_mode(
+                metadata.validator_flags.as_deref().unwrap_or_default(),
+                imported,
+            );
+            imported.problem.validation = ValidationType::LiteralChecker;
+            imported.problem.checker = Some(Checker::literal(mode));
+            return Ok(());
+        },
+        (Some("custom"), Some("interactive")) => {
+            imported.problem.validation = ValidationType::Interactive;
+            imported.problem.interactor = Some(InteractorSpec {
+                source: output_validator(package)?,
+                language: Language::Cpp17,
+                time_limit: imported.problem.time_limit,
+                query_limit: None,
+                protocol: InteractionProtocol::Stdio,
+            });
+            imported
+                .warn("the interactor was imported as a testlib interactor, review its exit codes");
+            return Ok(());
+        },
+        (Some("custom"), _) => imported.problem.validation = ValidationType::TestlibChecker,
+        (Some(other), _) => return Err(anyhow!("unknown validation {}", other)),
+    }
+
+    if let Some(flags) = &metadata.validator_flags {
+        imported.warn(format!("validator flags `{}` were ignored", flags));
+    }
+    imported.problem.checker = Some(Checker {
+        kind: CheckerKind::Kattis,
+        language: Some(Language::Cpp17),
+        source: Some(output_validator(package)?),
+        artifact_sha256: None,
+        compile_status: CheckerCompileStatus::Pending,
+    });
+    Ok(())
+}
+
+/// Source of the first c++ file in `output_validators`.
+fn output_validator(package: &Path) -> Result<String> {
+    let source = cpp_source_in(&package.join("output_validators"))
+        .ok_or_else(|| anyhow!("custom validation without a c++ output validator"))?;
+    read_to_string(&source)
+}
+
+/// First c++ file in `directory` or in one of its subdirectories.
+fn cpp_source_in(directory: &Path) -> Option<PathBuf> {
+    fs::read_dir(directory)
+        .ok()?
+        .flatten()
+        .flat_map(|entry| {
+            if entry.path().is_dir() {
+                fs::read_dir(entry.path())
+                    .into_iter()
+                    .flatten()
+                    .flatten()
+                    .collect()
+            } else {
+                vec![entry]
+            }
+        })
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "cpp" || extension == "cc")
+        })
+}
+
+/// `LiteralCheckMode` closest to the Kattis default validator called with `flags`.
+fn literal_mode(flags: &str, imported: &mut ImportedProblem) -> LiteralCheckMode {
+    let (mut case_sensitive, mut space_change_sensitive) = (false, false);
+    let (mut absolute_epsilon, mut relative_epsilon) = (None, None);
+    let mut flags = flags.split_whitespace();
+    while let Some(flag) = flags.next() {
+        let mut epsilon = || flags.next().and_then(|value| value.parse::<f64>().ok());
+        match flag {
+            "case_sensitive" => case_sensitive = true,
+            "space_change_sensitive" => space_change_sensitive = true,
+            "float_tolerance" => {
+                let value = epsilon();
+                (absolute_epsilon, relative_epsilon) = (value, value);
+            },
+            "float_absolute_tolerance" => absolute_epsilon = epsilon(),
+            "float_relative_tolerance" => relative_epsilon = epsilon(),
+            other => imported.warn(format!("validator flag `{}` was ignored", other)),
+        }
+    }
+    let tolerant = absolute_epsilon.is_some() || relative_epsilon.is_some();
+    if space_change_sensitive && (!case_sensitive || tolerant) {
+        imported
+            .warn("space_change_sensitive needs case_sensitive and no tolerance, it was ignored");
+    }
+    match (absolute_epsilon, relative_epsilon) {
+        (None, None) if case_sensitive && space_change_sensitive => LiteralCheckMode::Exact,
+        (None, None) if case_sensitive => LiteralCheckMode::Tokens,
+        (None, None) => LiteralCheckMode::CaseInsensitive,
+        (absolute_epsilon, relative_epsilon) => LiteralCheckMode::Float {
+            absolute_epsilon: absolute_epsilon.unwrap_or_default(),
+            relative_epsilon: relative_epsilon.unwrap_or_default(),
+        },
+    }
+}
+
+/// Flags for the Kattis default validator that make it behave like `mode`, the inverse of
+/// `literal_mode`.
+fn validator_flags(mode: &LiteralCheckMode) -> Option<String> {
+    match mode {
+        LiteralCheckMode::Exact => Some("case_sensitive space_change_sensitive".to_string()),
+        LiteralCheckMode::Tokens => Some("case_sensitive".to_string()),
+        LiteralCheckMode::CaseInsensitive => None,
+        LiteralCheckMode::Float {
+            absolute_epsilon,
+            relative_epsilon,
+        } if absolute_epsilon == relative_epsilon => Some(format!(
+            "case_sensitive float_tolerance {}",
+            absolute_epsilon
+        )),
+        LiteralCheckMode::Float {
+            absolute_epsilon,
+            relative_epsilon,
+        } => Some(format!(
+            "case_sensitive float_absolute_tolerance {} float_relative_tolerance {}",
+            absolute_epsilon, relative_epsilon
+        )),
+    }
+}
+
+/// `problem.<lang>.<extension>` files in `directory` keyed by locale.
+fn find_statements(directory: &Path, extension: &str) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let mut parts = file_name.split('.');
+            match (parts.next(), parts.next(), parts.next(), parts.next()) {
+                (Some("problem"), Some(language), Some(ext), None) if ext == extension => {
+                    Some((locale_from_language(language), entry.path()))
+                },
+                (Some("problem"), Some(ext), None, None) if ext == extension => {
+                    Some(("en".to_string(), entry.path()))
+                },
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn statement_name(metadata: &ProblemYaml, locale: &str) -> Option<String> {
+    match metadata.name.as_ref()? {
+        serde_yaml::Value::String(name) => Some(name.clone()),
+        serde_yaml::Value::Mapping(names) => names
+            .get(locale)
+            .and_then(|name| name.as_str())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+fn read_statement(text: &str, layout: &StatementLayout) -> ProblemStatement {
+    let (problem, rest) = split_at_heading(text, layout.input_heading);
+    let (input, rest) = split_at_heading(rest, layout.output_heading);
+    let (output, notes) = split_at_heading(rest, layout.notes_heading);
+    let mut statement = ProblemStatement {
+        name: String::new(),
+        information: String::new(),
+        input: input.trim().to_string(),
+        output: output.trim().to_string(),
+        problem: problem.trim().to_string(),
+        note: Some(notes.trim().to_string()).filter(|notes| !notes.is_empty()),
+    };
+    if let Some(line) = statement.problem.lines().next() {
+        let name = line
+            .strip_prefix("\\problemname{")
+            .and_then(|name| name.strip_suffix('}'))
+            .or_else(|| line.strip_prefix("# "));
+        if let Some(name) = name {
+            statement.name = name.trim().to_string();
+            statement.problem = statement.problem[line.len()..].trim().to_string();
+        }
+    }
+    statement
+}
+
+fn write_statement(statement: &ProblemStatement, layout: &StatementLayout) -> String {
+    let title = match layout.format {
+        StatementFormat::Latex => format!("\\problemname{{{}}}", statement.name),
+        _ => format!("# {}", statement.name),
+    };
+    let mut text = format!(
+        "{}\n\n{}\n\n{}\n\n{}\n\n{}\n\n{}\n",
+        title,
+        statement.problem,
+        layout.input_heading,
+        statement.input,
+        layout.output_heading,
+        statement.output
+    );
+    if let Some(note) = &statement.note {
+        text.push_str(&format!("\n{}\n\n{}\n", layout.notes_heading, note));
+    }
+    text
+}
+
+fn split_at_heading<'a>(text: &'a str, heading: &str) -> (&'a str, &'a str) {
+    match text.find(heading) {
+        Some(start) => (&text[..start], &text[start + heading.len()..]),
+        None => (text, ""),
+    }
+}
+
+/// Solutions in `submissions/<verdict>/`, each one a single file named after its path
+/// relative to `submissions`.
+fn import_solutions(submissions: &Path, imported: &mut ImportedProblem) -> Result<()> {
+    let Ok(verdicts) = fs::read_dir(submissions) else {
+        return Ok(());
+    };
+    for verdict in verdicts.flatten().filter(|entry| entry.path().is_dir()) {
+        let expected = match verdict.file_name().to_string_lossy().as_ref() {
+            "accepted" => ExpectedVerdict::Accepted,
+            "wrong_answer" => ExpectedVerdict::WrongAnswer,
+            "time_limit_exceeded" => ExpectedVerdict::TimeLimitExceeded,
+            _ => ExpectedVerdict::Any,
+        };
+        for path in fs::read_dir(verdict.path())?
+            .flatten()
+            .map(|entry| entry.path())
+        {
+            let language = match path.extension().and_then(|extension| extension.to_str()) {
+                Some("cpp" | "cc") => Language::Cpp17,
+                Some("c") => Language::C,
+                Some("java") => Language::Java,
+                Some("py") => Language::Python3,
+                _ => {
+                    imported.warn(format!("solution {} was not imported", path.display()));
+                    continue;
+                },
+            };
+            imported.problem.solutions.push(ProblemSolution {
+                name: path
+                    .strip_prefix(submissions)
+                    .unwrap_or(&path)
+                    .to_string_lossy()
+                    .into_owned(),
+                language,
+                source: read_to_string(&path)?,
+                expected,
+            });
+        }
+    }
+    imported
+        .problem
+        .solutions
+        .sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(())
+}
+
+/// `.in`/`.ans` pairs under `directory` sorted by path, files inside a subdirectory belong
+/// to the group named after it.
+fn test_cases_in(
+    directory: &Path,
+    imported: &mut ImportedProblem,
+) -> Result<Vec<(Option<String>, PathBuf, PathBuf)>> {
+    if !directory.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut pending = vec![directory.to_path_buf()];
+    let mut test_cases = Vec::new();
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "in") {
+                let answer = path.with_extension("ans");
+                if !answer.is_file() {
+                    imported.warn(format!("{} has no answer file", path.display()));
+                    continue;
+                }
+                let group = current
+                    .strip_prefix(directory)
+                    .ok()
+                    .filter(|group| !group.as_os_str().is_empty())
+                    .map(|group| group.to_string_lossy().into_owned());
+                test_cases.push((group, path, answer));
+            }
+        }
+    }
+    test_cases.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(test_cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::{export, import};
+    use crate::{
+        checker::LiteralCheckMode,
+        problem::{
+            Checker, CheckerKind, Locale, ProblemStatement, StatementFormat, ValidationType,
+        },
+    };
+
+    #[test]
+    fn kattis_package_round_trips() {
+        let package = std::env::temp_dir().join(format!("kattis-{}", Uuid::new_v4()));
+        fs::create_dir_all(package.join("problem_statement")).unwrap();
+        fs::create_dir_all(package.join("data/sample")).unwrap();
+        fs::create_dir_all(package.join("data/secret")).unwrap();
+        fs::write(
+            package.join("problem.yaml"),
+            "name: Hello\nvalidation: default\nlimits:\n  memory: 256\n",
+        )
+        .unwrap();
+        fs::write(package.join(".timelimit"), "2\n").unwrap();
+        fs::write(
+            package.join("problem_statement/problem.en.tex"),
+            "\\problemname{Hello}\nSay hello.\n\\section*{Input}\nNothing.\n\\section*{Output}\nhello\n",
+        )
+        .unwrap();
+        fs::write(package.join("data/sample/1.in"), "").unwrap();
+        fs::write(package.join("data/sample/1.ans"), "hello\n").unwrap();
+        fs::write(package.join("data/secret/1.in"), "").unwrap();
+        fs::write(package.join("data/secret/1.ans"), "hello\n").unwrap();
+        fs::write(package.join("data/secret/2.in"), "").unwrap();
+
+        let imported = import(&package, Uuid::new_v4()).unwrap();
+        let problem = &imported.problem;
+        assert_eq!(problem.body.name, "Hello");
+        assert_eq!(problem.body.problem, "Say hello.");
+        assert_eq!(problem.body.output, "hello");
+        assert_eq!(problem.body.format, StatementFormat::Latex);
+        assert_eq!(problem.body.examples.len(), 1);
+        assert_eq!(problem.time_limit, 2);
+        assert_eq!(problem.memory_limit, 256);
+        assert!(matches!(problem.validation, ValidationType::LiteralChecker));
+        assert_eq!(imported.test_cases.len(), 2);
+        assert!(imported.test_cases[0].is_sample);
+        assert_eq!(imported.warnings.len(), 1);
+        assert!(imported.warnings[0].contains("has no answer file"));
+
+        let exported = package.join("exported");
+        export(problem, &imported.test_cases, &exported).unwrap();
+        let secret_files = fs::read_dir(exported.join("data/secret")).unwrap().count();
+        let reimported = import(&exported, Uuid::new_v4()).unwrap();
+
+        let mut escaping = imported.test_cases.clone();
+        escaping[1].group = Some("../..".to_string());
+        assert!(export(problem, &escaping, &package.join("escaping-group")).is_err());
+        let mut escaping = problem.clone();
+        escaping.body.translations.insert(
+            Locale("../../../es".to_string()),
+            ProblemStatement::default(),
+        );
+        assert!(export(
+            &escaping,
+            &imported.test_cases,
+            &package.join("escaping-locale")
+        )
+        .is_err());
+
+        let modes = [
+            LiteralCheckMode::Exact,
+            LiteralCheckMode::Tokens,
+            LiteralCheckMode::CaseInsensitive,
+            LiteralCheckMode::Float {
+                absolute_epsilon: 1e-6,
+                relative_epsilon: 1e-6,
+            },
+            LiteralCheckMode::Float {
+                absolute_epsilon: 0.001,
+                relative_epsilon: 0.5,
+            },
+        ];
+        for (index, mode) in modes.into_iter().enumerate() {
+            let mut problem = problem.clone();
+            problem.checker = Some(Checker::literal(mode.clone()));
+            let exported = package.join(format!("mode-{}", index));
+            export(&problem, &imported.test_cases, &exported).unwrap();
+            let reimported = import(&exported, Uuid::new_v4()).unwrap();
+            assert_eq!(reimported.problem.checker, Some(Checker::literal(mode)));
+        }
+        fs::remove_dir_all(&package).unwrap();
+
+        assert_eq!(secret_files, 2);
+
+        assert_eq!(reimported.problem.body.name, "Hello");
+        assert_eq!(reimported.problem.body.problem, "Say hello.");
+        assert_eq!(reimported.problem.time_limit, 2);
+        assert_eq!(reimported.problem.body.examples.len(), 1);
+        assert_eq!(reimported.test_cases.len(), 2);
+        assert_eq!(
+            reimported
+                .test_cases
+                .iter()
+                .filter(|test_case| test_case.is_sample)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn output_validators_keep_kattis_exit_codes() {
+        let package = std::env::temp_dir().join(format!("kattis-{}", Uuid::new_v4()));
+        fs::create_dir_all(package.join("data/secret")).unwrap();
+        fs::create_dir_all(package.join("output_validators/checker")).unwrap();
+        fs::write(
+            package.join("problem.yaml"),
+            "name: Hello\nvalidation: custom\n",
+        )
+        .unwrap();
+        fs::write(package.join("data/secret/1.in"), "").unwrap();
+        fs::write(package.join("data/secret/1.ans"), "hello\n").unwrap();
+        fs::write(
+            package.join("output_validators/checker/check.cpp"),
+            "int main() { return 42; }\n",
+        )
+        .unwrap();
+
+        let imported = import(&package, Uuid::new_v4()).unwrap();
+        let checker = imported.problem.checker.clone().unwrap();
+        assert_eq!(checker.kind, CheckerKind::Kattis);
+
+        let exported_warnings = |kind: CheckerKind| {
+            let mut problem = imported.problem.clone();
+            problem.checker = Some(Checker {
+                kind,
+                ..checker.clone()
+            });
+            let exported = package.join(format!("exported-{}", Uuid::new_v4()));
+            export(&problem, &imported.test_cases, &exported)
+                .unwrap()
+                .iter()
+                .filter(|warning| warning.contains("42/43"))
+                .count()
+        };
+        let kattis = exported_warnings(CheckerKind::Kattis);
+        let testlib = exported_warnings(CheckerKind::Testlib);
+        let exit_code = exported_warnings(CheckerKind::ExitCode);
+        fs::remove_dir_all(&package).unwrap();
+
+        assert_eq!((kattis, testlib, exit_code), (0, 1, 1));
+    }
+
+    #[test]
+    fn oversized_memory_limit_is_rejected() {
+        let package = std::env::temp_dir().join(format!("kattis-{}", Uuid::new_v4()));
+        fs::create_dir_all(package.join("data/secret")).unwrap();
+        fs::write(
+            package.join("problem.yaml"),
+            format!("name: Hello\nlimits:\n  memory: {}\n", u64::MAX),
+        )
+        .unwrap();
+        fs::write(package.join("data/secret/1.in"), "").unwrap();
+        fs::write(package.join("data/secret/1.ans"), "hello\n").unwrap();
+
+        let imported = import(&package, Uuid::new_v4());
+        fs::remove_dir_all(&package).unwrap();
+
+        assert!(imported.is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//! Conversion between `Problem` and the package formats used by other judges.
+pub mod archive;
+pub mod kattis;
+pub mod polygon;
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::problem::{Problem, TestCaseInfo};
+
+/// A problem read from a package, test cases point to the files inside the package.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedProblem {
+    pub problem: Problem,
+    pub test_cases: Vec<TestCaseInfo>,
+    /// features of the package that could not be represented and were dropped
+    pub warnings: Vec<String>,
+}
+
+impl ImportedProblem {
+    fn warn(&mut self, warning: impl Into<String>) {
+        self.warnings.push(warning.into());
+    }
+}
+
+fn read_to_string(path: &Path) -> Result<String> {
+    std::fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))
+}
+
+/// Joins `path`, as written in a package manifest, to `package`. Absolute paths and `..` are
+/// rejected so a crafted manifest cannot read files outside the package.
+fn package_path(package: &Path, path: &str) -> Result<PathBuf> {
+    let path = Path::new(path);
+    if !path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(anyhow!("{} points outside the package", path.display()));
+    }
+    Ok(package.join(path))
+}
+
+/// Maps a language name as written by Polygon (`english`) or an ISO code (`en`) to a locale.
+fn locale_from_language(language: &str) -> String {
+    match language.to_ascii_lowercase().as_str() {
+        "english" => "en".to_string(),
+        "spanish" => "es".to_string(),
+        "russian" => "ru".to_string(),
+        "portuguese" => "pt".to_string(),
+        "french" => "fr".to_string(),
+        other => other.chars().take(2).collect(),
+    }
+}
+
+/// Milliseconds to the whole seconds `Problem::time_limit` can hold.
+fn time_limit_from_millis(millis: u64, imported: &mut ImportedProblem) -> u16 {
+    let seconds = millis.div_ceil(1000).clamp(1, 10);
+    if seconds * 1000 != millis {
+        imported.warn(format!(
+            "time limit of {}ms was rounded to {}s",
+            millis, seconds
+        ));
+    }
+    seconds as u16
+}
+
+/// Bytes to the megabytes `Problem::memory_limit` can hold.
+fn memory_limit_from_bytes(bytes: u64, imported: &mut ImportedProblem) -> u16 {
+    let megabytes = bytes / (1024 * 1024);
+    let clamped = megabytes.clamp(256, 512);
+    if clamped != megabytes {
+        imported.warn(format!(
+            "memory limit of {}MB was changed to {}MB",
+            megabytes, clamped
+        ));
+    }
+    clamped as u16
+}
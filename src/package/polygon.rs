@@ -0,0 +1,509 @@
+//! Import of unpacked [Polygon](https://polygon.codeforces.com) packages.
+//!
+//! Only the pieces `Problem` can represent are read: the statement sections, the limits and
+//! tests of the main testset and the checker. Anything else found in `problem.xml` is reported
+//! as a warning.
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use roxmltree::{Document, Node};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::{
+    locale_from_language, memory_limit_from_bytes, package_path, read_to_string,
+    time_limit_from_millis, ImportedProblem,
+};
+use crate::{
+    checker::LiteralCheckMode,
+    contest::Language,
+    generator::GeneratedTest,
+    input_validator::Validator,
+    interactor::{InteractionProtocol, InteractorSpec},
+    problem::{
+        Checker, Locale, ProblemBody, ProblemExample, ProblemStatement, StatementFormat,
+        TestCaseInfo, ValidationType,
+    },
+    solution::{ExpectedVerdict, ProblemSolution},
+    storage::metadata_of,
+};
+
+/// Statement languages, the first one found becomes the default locale.
+const PREFERRED_LANGUAGES: [&str; 2] = ["spanish", "english"];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProblemProperties {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    legend: String,
+    #[serde(default)]
+    input: String,
+    #[serde(default)]
+    output: String,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    sample_tests: Vec<ProblemExample>,
+}
+
+pub fn import(package: &Path, submitted_by: Uuid) -> Result<ImportedProblem> {
+    let xml = read_to_string(&package.join("problem.xml"))?;
+    let document = Document::parse(&xml).context("problem.xml is not valid xml")?;
+    let root = document.root_element();
+    if !root.has_tag_name("problem") {
+        return Err(anyhow!("problem.xml has no <problem> root"));
+    }
+
+    let mut imported = ImportedProblem::default();
+    imported.problem.submitted_by = submitted_by;
+    imported.problem.created_at = chrono::Utc::now();
+
+    import_statements(package, &root, &mut imported)?;
+    import_testset(package, &root, &mut imported)?;
+    import_assets(package, &root, &mut imported)?;
+
+    for property in descendants(&root, "property") {
+        imported.warn(format!(
+            "property {} is not supported",
+            property.attribute("name").unwrap_or_default()
+        ));
+    }
+    Ok(imported)
+}
+
+fn import_statements(package: &Path, root: &Node, imported: &mut ImportedProblem) -> Result<()> {
+    let mut languages: Vec<&str> = descendants(root, "statement")
+        .filter(|statement| statement.attribute("type") == Some("application/x-tex"))
+        .filter_map(|statement| statement.attribute("language"))
+        .collect();
+    languages.sort_by_key(|language| {
+        PREFERRED_LANGUAGES
+            .iter()
+            .position(|preferred| preferred == language)
+            .unwrap_or(PREFERRED_LANGUAGES.len())
+    });
+    languages.dedup();
+
+    let Some((default_language, translations)) = languages.split_first() else {
+        imported.warn("package has no tex statement");
+        return Ok(());
+    };
+    let (statement, examples) = read_statement(package, default_language)?;
+    imported.problem.body = ProblemBody {
+        name: statement.name,
+        information: String::new(),
+        identifier: root.attribute("short-name").map(str::to_string),
+        input: statement.input,
+        output: statement.output,
+        problem: statement.problem,
+        note: statement.note,
+        examples,
+        locale: Locale(locale_from_language(default_language)),
+        translations: Default::default(),
+        format: StatementFormat::Latex,
+    };
+    for language in translations {
+        let (statement, _) = read_statement(package, language)?;
+        imported
+            .problem
+            .body
+            .translations
+            .insert(Locale(locale_from_language(language)), statement);
+    }
+    Ok(())
+}
+
+fn read_statement(
+    package: &Path,
+    language: &str,
+) -> Result<(ProblemStatement, Vec<ProblemExample>)> {
+    let properties_path =
+        package_path(&package.join("statements"), language)?.join("problem-properties.json");
+    let properties = if properties_path.is_file() {
+        serde_json::from_str(&read_to_string(&properties_path)?)
+            .with_context(|| format!("{} is malformed", properties_path.display()))?
+    } else {
+        let sections = package_path(&package.join("statement-sections"), language)?;
+        let section = |name: &str| read_to_string(&sections.join(name)).unwrap_or_default();
+        ProblemProperties {
+            name: section("name.tex").trim().to_string(),
+            legend: section("legend.tex"),
+            input: section("input.tex"),
+            output: section("output.tex"),
+            notes: section("notes.tex"),
+            sample_tests: Vec::new(),
+        }
+    };
+    let statement = ProblemStatement {
+        name: properties.name,
+        information: String::new(),
+        input: properties.input,
+        output: properties.output,
+        problem: properties.legend,
+        note: Some(properties.notes).filter(|notes| !notes.trim().is_empty()),
+    };
+    Ok((statement, properties.sample_tests))
+}
+
+fn import_testset(package: &Path, root: &Node, imported: &mut ImportedProblem) -> Result<()> {
+    let judging = child(root, "judging").ok_or_else(|| anyhow!("problem.xml has no <judging>"))?;
+    for io in ["input-file", "output-file"] {
+        if judging.attribute(io).is_some_and(|file| !file.is_empty()) {
+            imported.warn(format!("{} is not supported, standard io will be used", io));
+        }
+    }
+    let mut testsets = judging
+        .children()
+        .filter(|node| node.has_tag_name("testset"));
+    let testset = testsets
+        .next()
+        .ok_or_else(|| anyhow!("problem.xml has no <testset>"))?;
+    for ignored in testsets {
+        imported.warn(format!(
+            "testset {} was ignored",
+            ignored.attribute("name").unwrap_or_default()
+        ));
+    }
+
+    let time_limit = child_text(&testset, "time-limit")
+        .ok_or_else(|| anyhow!("testset has no time limit"))?
+        .parse()?;
+    imported.problem.time_limit = time_limit_from_millis(time_limit, imported);
+    let memory_limit = child_text(&testset, "memory-limit")
+        .ok_or_else(|| anyhow!("testset has no memory limit"))?
+        .parse()?;
+    imported.problem.memory_limit = memory_limit_from_bytes(memory_limit, imported);
+
+    let input_pattern = child_text(&testset, "input-path-pattern")
+        .ok_or_else(|| anyhow!("testset has no input path pattern"))?;
+    let answer_pattern = child_text(&testset, "answer-path-pattern")
+        .ok_or_else(|| anyhow!("testset has no answer path pattern"))?;
+
+    let tests = descendants(&testset, "test");
+    for (index, test) in tests.enumerate() {
+        let ordinal = index + 1;
+        let stdin_path = package_path(package, &expand_path_pattern(input_pattern, ordinal))?;
+        let stdout_path = package_path(package, &expand_path_pattern(answer_pattern, ordinal))?;
+        if !stdin_path.is_file() || !stdout_path.is_file() {
+            imported.warn(format!(
+                "test {} is missing from the package, build a full package to include it",
+                ordinal
+            ));
+            continue;
+        }
+        let points = match test.attribute("points") {
+            Some(points) => {
+                let points: f64 = points.parse()?;
+                if points.fract() != 0.0 {
+                    imported.warn(format!("points of test {} were truncated", ordinal));
+                }
+                Some(points as u32)
+            },
+            None => None,
+        };
+        let test_case = TestCaseInfo {
+            stdin_metadata: Some(metadata_of(&stdin_path)?),
+            stdout_metadata: Some(metadata_of(&stdout_path)?),
+            stdin_path: path_to_string(stdin_path),
+            stdout_path: path_to_string(stdout_path),
+            problem_id: imported.problem.id.clone(),
+            id: Uuid::new_v4(),
+            ordinal: ordinal as u32,
+            group: test.attribute("group").map(str::to_string),
+            is_sample: test.attribute("sample") == Some("true"),
+            points,
+            description: test
+                .attribute("description")
+                .or_else(|| test.attribute("cmd"))
+                .map(str::to_string),
+            generated_by: test
+                .attribute("cmd")
+                .filter(|_| test.attribute("method") == Some("generated"))
+                .and_then(|cmd| GeneratedTest::from_command(ordinal as u32, cmd)),
+        };
+        imported.problem.test_cases.push(test_case.id);
+        imported.test_cases.push(test_case);
+    }
+    Ok(())
+}
+
+fn import_assets(package: &Path, root: &Node, imported: &mut ImportedProblem) -> Result<()> {
+    let Some(assets) = child(root, "assets") else {
+        return Ok(());
+    };
+    if let Some(checker) = child(&assets, "checker") {
+        let name = checker.attribute("name").unwrap_or_default();
+        if let Some(mode) = literal_mode(name) {
+            imported.problem.validation = ValidationType::LiteralChecker;
+            imported.problem.checker = Some(Checker::literal(mode));
+        } else {
+            let (source, language) = program_source(package, &checker, imported)?;
+            imported.problem.validation = ValidationType::TestlibChecker;
+            imported.problem.checker = Some(Checker::testlib(source, language));
+        }
+    }
+    if let Some(interactor) = child(&assets, "interactor") {
+        let (source, language) = program_source(package, &interactor, imported)?;
+        imported.problem.validation = ValidationType::Interactive;
+        imported.problem.interactor = Some(InteractorSpec {
+            source,
+            language,
+            time_limit: imported.problem.time_limit,
+            query_limit: None,
+            protocol: InteractionProtocol::Stdio,
+        });
+    }
+    if let Some(validators) = child(&assets, "validators") {
+        let mut validators = validators
+            .children()
+            .filter(|node| node.has_tag_name("validator"));
+        if let Some(validator) = validators.next() {
+            let (source, language) = program_source(package, &validator, imported)?;
+            imported.problem.validator = Some(Validator::new(source, language));
+        }
+        if validators.next().is_some() {
+            imported.warn("only the first validator was imported");
+        }
+    }
+    for solution in descendants(&assets, "solution") {
+        import_solution(package, &solution, imported)?;
+    }
+    Ok(())
+}
+
+fn import_solution(package: &Path, solution: &Node, imported: &mut ImportedProblem) -> Result<()> {
+    let (path, kind) = child(solution, "source")
+        .and_then(|source| Some((source.attribute("path")?, source.attribute("type")?)))
+        .ok_or_else(|| anyhow!("solution has no source"))?;
+    let name = Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned());
+    let Some(language) = solution_language(kind) else {
+        imported.warn(format!(
+            "solution {} was not imported, {} is not supported",
+            path, kind
+        ));
+        return Ok(());
+    };
+    let expected = match solution.attribute("tag").unwrap_or_default() {
+        "main" | "accepted" => ExpectedVerdict::Accepted,
+        "wrong-answer" => ExpectedVerdict::WrongAnswer,
+        "time-limit-exceeded" => ExpectedVerdict::TimeLimitExceeded,
+        _ => ExpectedVerdict::Any,
+    };
+    imported.problem.solutions.push(ProblemSolution {
+        name: name.unwrap_or_else(|| path.to_string()),
+        language,
+        source: read_to_string(&package_path(package, path)?)?,
+        expected,
+    });
+    Ok(())
+}
+
+/// Polygon language names look like `cpp.g++17`, `java11` or `python.3`.
+fn solution_language(kind: &str) -> Option<Language> {
+    match kind {
+        kind if kind.starts_with("cpp") && kind.contains("11") => Some(Language::Cpp11),
+        kind if kind.starts_with("cpp") && kind.contains("14") => Some(Language::Cpp14),
+        kind if kind.starts_with("cpp") => Some(Language::Cpp17),
+        kind if kind.starts_with("c.") => Some(Language::C),
+        kind if kind.starts_with("java") => Some(Language::Java),
+        kind if kind.starts_with("python.3") || kind.starts_with("python3") => {
+            Some(Language::Python3)
+        },
+        _ => None,
+    }
+}
+
+/// Source and language of a checker or interactor, Polygon names languages like `cpp.g++17`.
+fn program_source(
+    package: &Path,
+    program: &Node,
+    imported: &mut ImportedProblem,
+) -> Result<(String, Language)> {
+    let name = program.tag_name().name();
+    let source = child(program, "source")
+        .and_then(|source| Some((source.attribute("path")?, source.attribute("type"))))
+        .ok_or_else(|| anyhow!("{} has no source", name))?;
+    let language = match source.1.unwrap_or_default() {
+        kind if kind.starts_with("cpp") && kind.contains("11") => Language::Cpp11,
+        kind if kind.starts_with("cpp") && kind.contains("14") => Language::Cpp14,
+        kind => {
+            if !kind.starts_with("cpp") {
+                imported.warn(format!("{} type `{}` was imported as c++17", name, kind));
+            }
+            Language::Cpp17
+        },
+    };
+    Ok((read_to_string(&package_path(package, source.0)?)?, language))
+}
+
+/// Testlib standard checkers that `checker::literal_check` can replace.
+fn literal_mode(name: &str) -> Option<LiteralCheckMode> {
+    let float = |epsilon: f64| LiteralCheckMode::Float {
+        absolute_epsilon: epsilon,
+        relative_epsilon: epsilon,
+    };
+    match name {
+        "std::wcmp.cpp" => Some(LiteralCheckMode::Tokens),
+        "std::fcmp.cpp" => Some(LiteralCheckMode::Exact),
+        "std::rcmp4.cpp" => Some(float(1e-4)),
+        "std::rcmp6.cpp" => Some(float(1e-6)),
+        "std::rcmp9.cpp" => Some(float(1e-9)),
+        _ => None,
+    }
+}
+
+/// Expands printf like patterns as used by Polygon, i.e. `tests/%02d`.
+fn expand_path_pattern(pattern: &str, ordinal: usize) -> String {
+    let Some(start) = pattern.find('%') else {
+        return pattern.to_string();
+    };
+    let Some(length) = pattern[start..].find('d') else {
+        return pattern.to_string();
+    };
+    let width = pattern[start + 1..start + length]
+        .trim_start_matches('0')
+        .parse()
+        .unwrap_or(0);
+    format!(
+        "{}{:0width$}{}",
+        &pattern[..start],
+        ordinal,
+        &pattern[start + length + 1..],
+        width = width
+    )
+}
+
+fn path_to_string(path: PathBuf) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+fn child<'a, 'input>(node: &Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn child_text<'a>(node: &Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name)
+        .and_then(|child| child.text())
+        .map(str::trim)
+}
+
+fn descendants<'a, 'input: 'a>(
+    node: &Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.descendants()
+        .filter(move |node| node.has_tag_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::{expand_path_pattern, import, program_source};
+    use crate::{
+        contest::Language,
+        package::ImportedProblem,
+        problem::{StatementFormat, ValidationType},
+    };
+
+    const PROBLEM_XML: &str = r#"<?xml version="1.0" encoding="utf-8" standalone="no"?>
+<problem revision="1" short-name="a-plus-b">
+    <names><name language="english" value="A+B"/></names>
+    <statements>
+        <statement charset="UTF-8" language="english" path="statements/english/problem.tex" type="application/x-tex"/>
+    </statements>
+    <judging input-file="" output-file="">
+        <testset name="tests">
+            <time-limit>1500</time-limit>
+            <memory-limit>268435456</memory-limit>
+            <test-count>2</test-count>
+            <input-path-pattern>tests/%02d</input-path-pattern>
+            <answer-path-pattern>tests/%02d.a</answer-path-pattern>
+            <tests>
+                <test method="manual" sample="true"/>
+                <test cmd="gen 10" method="generated"/>
+            </tests>
+        </testset>
+    </judging>
+    <assets>
+        <checker name="std::ncmp.cpp" type="testlib">
+            <source path="files/check.cpp" type="cpp.g++17"/>
+        </checker>
+    </assets>
+</problem>"#;
+
+    #[test]
+    fn path_patterns_are_expanded() {
+        assert_eq!(expand_path_pattern("tests/%02d", 3), "tests/03");
+        assert_eq!(expand_path_pattern("tests/%d.a", 12), "tests/12.a");
+    }
+
+    #[test]
+    fn program_languages_are_read_from_cpp_types_only() {
+        let package = std::env::temp_dir().join(format!("polygon-{}", Uuid::new_v4()));
+        fs::create_dir_all(package.join("files")).unwrap();
+        fs::write(package.join("files/check.cpp"), "#include \"testlib.h\"\n").unwrap();
+        let language = |kind: &str| {
+            let xml = format!(
+                r#"<checker><source path="files/check.cpp" type="{}"/></checker>"#,
+                kind
+            );
+            let document = roxmltree::Document::parse(&xml).unwrap();
+            let mut imported = ImportedProblem::default();
+            let (_, language) =
+                program_source(&package, &document.root_element(), &mut imported).unwrap();
+            (language, imported.warnings.len())
+        };
+
+        assert_eq!(language("cpp.g++11"), (Language::Cpp11, 0));
+        assert_eq!(language("cpp.msys2-mingw64-9-g++14"), (Language::Cpp14, 0));
+        assert_eq!(language("cpp.g++17"), (Language::Cpp17, 0));
+        assert_eq!(language("python.3.11"), (Language::Cpp17, 1));
+        assert_eq!(language("java11"), (Language::Cpp17, 1));
+        fs::remove_dir_all(&package).unwrap();
+    }
+
+    #[test]
+    fn polygon_package_is_imported() {
+        let package = std::env::temp_dir().join(format!("polygon-{}", Uuid::new_v4()));
+        fs::create_dir_all(package.join("statements/english")).unwrap();
+        fs::create_dir_all(package.join("tests")).unwrap();
+        fs::create_dir_all(package.join("files")).unwrap();
+        fs::write(package.join("problem.xml"), PROBLEM_XML).unwrap();
+        fs::write(
+            package.join("statements/english/problem-properties.json"),
+            r#"{"name": "A+B", "legend": "Add $a$ and $b$.", "input": "Two integers.",
+                "output": "Their sum.", "notes": "", "sampleTests": [{"input": "1 2\n", "output": "3\n"}]}"#,
+        )
+        .unwrap();
+        fs::write(package.join("tests/01"), "1 2\n").unwrap();
+        fs::write(package.join("tests/01.a"), "3\n").unwrap();
+        fs::write(package.join("files/check.cpp"), "#include \"testlib.h\"\n").unwrap();
+
+        let imported = import(&package, Uuid::new_v4()).unwrap();
+        let outside = PROBLEM_XML.replace("files/check.cpp", "../check.cpp");
+        fs::write(package.join("problem.xml"), outside).unwrap();
+        let escaped = import(&package, Uuid::new_v4());
+        fs::remove_dir_all(&package).unwrap();
+        assert!(escaped.is_err());
+
+        let problem = &imported.problem;
+        assert_eq!(problem.body.name, "A+B");
+        assert_eq!(problem.body.locale.0, "en");
+        assert_eq!(problem.body.format, StatementFormat::Latex);
+        assert_eq!(problem.body.examples.len(), 1);
+        assert_eq!(problem.time_limit, 2);
+        assert_eq!(problem.memory_limit, 256);
+        assert!(matches!(problem.validation, ValidationType::TestlibChecker));
+        assert!(problem.checker.is_some());
+        assert_eq!(imported.test_cases.len(), 1);
+        assert!(imported.test_cases[0].is_sample);
+        assert_eq!(imported.warnings.len(), 2);
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+
+use chrono::{serde::ts_milliseconds, DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    contest::Contest,
+    problem::{ContestId, ProblemId},
+    status::Status,
+    team::ContestParticipant,
+};
+
+/// A user replaying an ended contest as if it were live, starting at `start_time`.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct VirtualParticipation {
+    pub user_id: Uuid,
+    pub contest_id: ContestId,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub start_time: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VirtualParticipationError {
+    /// only ended contests can be replayed, otherwise the virtual participant could see the
+    /// problems before the official participants finish
+    ContestNotEnded,
+}
+
+impl fmt::Display for VirtualParticipationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VirtualParticipationError::ContestNotEnded => {
+                write!(
+                    f,
+                    "virtual participations must start after the contest ends"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for VirtualParticipationError {}
+
+impl VirtualParticipation {
+    pub fn new(
+        user_id: Uuid,
+        contest: &Contest,
+        start_time: DateTime<Utc>,
+    ) -> Result<Self, VirtualParticipationError> {
+        let participation = Self {
+            user_id,
+            contest_id: contest.id.clone(),
+            start_time,
+        };
+        if !participation.starts_after(contest) {
+            return Err(VirtualParticipationError::ContestNotEnded);
+        }
+        Ok(participation)
+    }
+
+    pub fn starts_after(&self, contest: &Contest) -> bool {
+        self.start_time >= contest.end_date
+    }
+
+    pub fn end_time(&self, contest: &Contest) -> DateTime<Utc> {
+        self.start_time + (contest.end_date - contest.start_date)
+    }
+
+    /// Always false for a participation that starts before the contest ends.
+    pub fn is_running(&self, contest: &Contest, now: &DateTime<Utc>) -> bool {
+        self.starts_after(contest) && self.start_time <= *now && *now < self.end_time(contest)
+    }
+
+    /// Time elapsed since the personal start, `None` if `submitted_at` falls outside the
+    /// virtual window.
+    pub fn elapsed(&self, contest: &Contest, submitted_at: &DateTime<Utc>) -> Option<Duration> {
+        if self.is_running(contest, submitted_at) {
+            Some(*submitted_at - self.start_time)
+        } else {
+            None
+        }
+    }
+
+    /// Maps a submission made during the virtual participation onto the original contest
+    /// timeline so it can be ranked next to the official submissions.
+    pub fn to_contest_time(
+        &self,
+        contest: &Contest,
+        submitted_at: &DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        self.elapsed(contest, submitted_at)
+            .map(|elapsed| contest.start_date + elapsed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, Deserialize, Serialize, TS, sqlx::Type)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "participation_kind", rename_all = "snake_case")]
+pub enum ParticipationKind {
+    Official,
+    Virtual,
+    Upsolve,
+}
+
+impl ParticipationKind {
+    /// Decides how a submission to `contest` counts, `None` if it was sent before the contest
+    /// started.
+    pub fn classify(
+        contest: &Contest,
+        virtual_participation: Option<&VirtualParticipation>,
+        submitted_at: &DateTime<Utc>,
+    ) -> Option<Self> {
+        if virtual_participation.is_some_and(|vp| vp.is_running(contest, submitted_at)) {
+            Some(ParticipationKind::Virtual)
+        } else if *submitted_at < contest.start_date {
+            None
+        } else if *submitted_at < contest.end_date {
+            Some(ParticipationKind::Official)
+        } else {
+            Some(ParticipationKind::Upsolve)
+        }
+    }
+}
+
+/// A submission as seen by the scoreboard, `elapsed` is measured from the contest start
+/// (or the personal start for virtual participations).
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ScoreboardSubmission {
+    pub submission_id: String,
+    pub participant: ContestParticipant,
+    pub problem_id: ProblemId,
+    pub status: Status,
+    pub kind: ParticipationKind,
+    /// milliseconds since the start
+    pub elapsed: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ScoreboardFilter {
+    pub official: bool,
+    #[serde(rename = "virtual")]
+    pub virtual_participation: bool,
+    pub upsolve: bool,
+}
+
+impl Default for ScoreboardFilter {
+    fn default() -> Self {
+        Self {
+            official: true,
+            virtual_participation: false,
+            upsolve: false,
+        }
+    }
+}
+
+impl ScoreboardFilter {
+    pub fn accepts(&self, kind: ParticipationKind) -> bool {
+        match kind {
+            ParticipationKind::Official => self.official,
+            ParticipationKind::Virtual => self.virtual_participation,
+            ParticipationKind::Upsolve => self.upsolve,
+        }
+    }
+
+    pub fn apply<'a>(
+        &'a self,
+        submissions: &'a [ScoreboardSubmission],
+    ) -> impl Iterator<Item = &'a ScoreboardSubmission> {
+        submissions
+            .iter()
+            .filter(|submission| self.accepts(submission.kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        contest::{Contest, ContestBody, ContestType, RegistrationPolicy},
+        participation::{ParticipationKind, VirtualParticipation, VirtualParticipationError},
+        problem::ContestId,
+    };
+
+    #[test]
+    fn virtual_submissions_are_shifted_to_contest_time() {
+        let start_date = Utc::now() - Duration::days(7);
+        let contest = Contest {
+            id: ContestId(1),
+            author: Uuid::new_v4(),
+            body: ContestBody::default(),
+            name: "Concurso".to_string(),
+            start_date,
+            end_date: start_date + Duration::hours(5),
+            contest_type: ContestType::ICPC,
+            problems: vec![],
+            is_frozen: false,
+            frozen_time: 0,
+            registration_policy: RegistrationPolicy::Open,
+            registration_start: None,
+            registration_end: None,
+            registration_password_hash: None,
+        };
+        let participation =
+            VirtualParticipation::new(Uuid::new_v4(), &contest, Utc::now() - Duration::hours(1))
+                .unwrap();
+
+        let submitted_at = participation.start_time + Duration::minutes(30);
+        assert_eq!(
+            participation.to_contest_time(&contest, &submitted_at),
+            Some(start_date + Duration::minutes(30))
+        );
+        assert_eq!(
+            ParticipationKind::classify(&contest, Some(&participation), &submitted_at),
+            Some(ParticipationKind::Virtual)
+        );
+
+        let after_window = participation.start_time + Duration::hours(6);
+        assert_eq!(participation.to_contest_time(&contest, &after_window), None);
+        assert_eq!(
+            ParticipationKind::classify(&contest, Some(&participation), &after_window),
+            Some(ParticipationKind::Upsolve)
+        );
+
+        let during_contest = contest.start_date + Duration::hours(1);
+        assert_eq!(
+            VirtualParticipation::new(Uuid::new_v4(), &contest, during_contest).unwrap_err(),
+            VirtualParticipationError::ContestNotEnded
+        );
+        let overlapping = VirtualParticipation {
+            user_id: Uuid::new_v4(),
+            contest_id: ContestId(1),
+            start_time: during_contest,
+        };
+        assert_eq!(
+            ParticipationKind::classify(&contest, Some(&overlapping), &during_contest),
+            Some(ParticipationKind::Official)
+        );
+    }
+}
@@ -1,20 +1,38 @@
-use std::{fmt, process::Output, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    process::Output,
+    time::Duration,
+};
 
 use anyhow::{anyhow, Result};
 use bit_vec::BitVec;
+use chrono::{serde::ts_milliseconds, DateTime};
 use serde::{Deserialize, Serialize};
 use sqlx::types::chrono::Utc;
 use ts_rs::TS;
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 use crate::{
+    checker::LiteralCheckMode,
     consts::{
-        CONTEST_ID_BITS, EMPTY_BITS, PROBLEM_ID_BITS, SUBMISSION_ID_BITS, TIMESTAMP_BITS,
+        CONTEST_ID_BITS, DEFAULT_LOCALE, EMPTY_BITS, MAX_CHECKER_FILE_SIZE_IN_BYTES,
+        MAX_TAGS_PER_PROBLEM, PROBLEM_ID_BITS, SUBMISSION_ID_BITS, TIMESTAMP_BITS,
         UUID_TIME_MID_BITS,
     },
+    contest::{Contest, Language},
+    diff::{diff, OutputDiff},
+    generator::{GeneratedTest, GeneratorScript},
+    input_validator::Validator,
+    interactor::{InteractionTranscript, InteractorSpec},
+    problemset::{ProblemMeta, Tag},
+    relations::Relations,
+    render::find_unsafe_markup_in_body,
     serde::external_struct,
+    solution::ProblemSolution,
     status::Status,
+    storage::{TestCaseFileMetadata, TestCaseStoreError},
     utils::empty_string_as_none,
 };
 /// # Id concurso (32 bits):
@@ -24,7 +42,7 @@ use crate::{
 /// uno.
 #[derive(TS)]
 #[ts(export)]
-#[derive(Default, Serialize, Deserialize, Debug, Clone)]
+#[derive(Default, Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct ContestId(pub u32);
 impl ContestId {
     pub fn as_u32(&self) -> u32 {
@@ -242,6 +260,8 @@ impl SubmissionId {
     }
 }
 
+/// The top level fields hold the statement in `locale`, other languages live in
+/// `translations` so bodies written before translations existed keep parsing.
 #[derive(Debug, Default, Serialize, Clone, Deserialize, TS)]
 #[ts(export)]
 pub struct ProblemBody {
@@ -255,16 +275,108 @@ pub struct ProblemBody {
     #[serde(deserialize_with = "empty_string_as_none")]
     pub note: Option<String>,
     pub examples: Vec<ProblemExample>,
+    #[serde(default)]
+    pub locale: Locale,
+    #[serde(default)]
+    pub translations: BTreeMap<Locale, ProblemStatement>,
+    /// markup used by every statement field, translations included
+    #[serde(default)]
+    pub format: StatementFormat,
 }
 
-#[derive(Debug, Default, Serialize, Clone, Deserialize, TS)]
+#[derive(Debug, Default, Serialize, Clone, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum StatementFormat {
+    /// CommonMark with `$inline$` and `$$display$$` math
+    #[default]
+    Markdown,
+    Latex,
+    Html,
+    PlainText,
+}
+
+impl ProblemBody {
+    /// Statement in the default locale.
+    pub fn statement(&self) -> ProblemStatement {
+        ProblemStatement {
+            name: self.name.clone(),
+            information: self.information.clone(),
+            input: self.input.clone(),
+            output: self.output.clone(),
+            problem: self.problem.clone(),
+            note: self.note.clone(),
+        }
+    }
+
+    /// Replaces `examples` with the contents of the sample tests so both never diverge.
+    pub fn sync_examples(&mut self, test_cases: &[TestCaseInfo]) -> Result<()> {
+        self.examples = examples_from_samples(test_cases)?;
+        Ok(())
+    }
+
+    pub fn locales(&self) -> impl Iterator<Item = &Locale> {
+        std::iter::once(&self.locale).chain(self.translations.keys())
+    }
+
+    /// Statement in `locale`, falling back to its base language (`en-US` -> `en`) and then
+    /// to the default locale.
+    pub fn localized(&self, locale: &str) -> ProblemStatement {
+        let base_language = locale.split(['-', '_']).next().unwrap_or(locale);
+        [locale, base_language]
+            .into_iter()
+            .find_map(|candidate| {
+                if self.locale.0.eq_ignore_ascii_case(candidate) {
+                    Some(self.statement())
+                } else {
+                    self.translations
+                        .iter()
+                        .find(|(key, _)| key.0.eq_ignore_ascii_case(candidate))
+                        .map(|(_, statement)| statement.clone())
+                }
+            })
+            .unwrap_or_else(|| self.statement())
+    }
+}
+
+/// BCP 47 language tag, i.e. `es` or `en-US`.
+#[derive(Debug, Serialize, Clone, Deserialize, TS, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+#[ts(export)]
+pub struct Locale(pub String);
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale(DEFAULT_LOCALE.to_string())
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Clone, Deserialize, TS, PartialEq, Eq)]
+#[ts(export)]
+pub struct ProblemStatement {
+    pub name: String,
+    pub information: String,
+    pub input: String,
+    pub output: String,
+    pub problem: String,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Clone, Deserialize, TS, PartialEq, Eq)]
 #[ts(export)]
 pub struct ProblemExample {
     pub input: String,
     pub output: String,
 }
 
-#[derive(Debug, Clone, TS, Validate, Default)]
+#[derive(Debug, Clone, TS, Validate, Default, Serialize, Deserialize)]
 #[ts(export)]
 pub struct Problem {
     pub id: ProblemId,
@@ -273,12 +385,149 @@ pub struct Problem {
     pub body: ProblemBody,
     pub checker: Option<Checker>,
     pub validation: ValidationType,
+    /// only for `ValidationType::Interactive`
+    #[serde(default)]
+    pub interactor: Option<InteractorSpec>,
+    #[serde(default)]
+    pub validator: Option<Validator>,
+    #[serde(default)]
+    pub generator_script: Option<GeneratorScript>,
+    #[serde(default)]
+    pub solutions: Vec<ProblemSolution>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub difficulty: Option<u16>,
+    #[serde(default)]
+    pub source: Option<String>,
     #[validate(range(min = 256, max = 512))] // memory in mb
     pub memory_limit: u16,
     #[validate(range(min = 1, max = 10))] // time limit in seconds
     pub time_limit: u16,
-    pub is_public: bool,
+    /// problems stored before visibility policies have an `is_public` flag instead
+    #[serde(default, alias = "is_public", deserialize_with = "visibility_or_is_public")]
+    pub visibility: Visibility,
     pub test_cases: Vec<Uuid>,
+    /// current `ProblemRevision`, bumped on every edit
+    #[serde(default = "first_revision")]
+    pub revision: u32,
+}
+
+pub(crate) fn first_revision() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[serde(tag = "type", content = "options", rename_all = "snake_case")]
+#[ts(export)]
+pub enum Visibility {
+    /// only the author and the problem setters
+    #[default]
+    Private,
+    Public,
+    /// participants see it once `contest` starts, everybody after it ends when
+    /// `reveal_after_end` is set
+    ContestOnly {
+        contest: ContestId,
+        reveal_after_end: bool,
+    },
+    /// public from the given moment on
+    Scheduled(
+        #[serde(with = "ts_milliseconds")]
+        #[ts(type = "number")]
+        DateTime<Utc>,
+    ),
+}
+
+impl From<bool> for Visibility {
+    /// Maps the old `is_public` flag.
+    fn from(is_public: bool) -> Self {
+        if is_public {
+            Visibility::Public
+        } else {
+            Visibility::Private
+        }
+    }
+}
+
+fn visibility_or_is_public<'de, D>(deserializer: D) -> Result<Visibility, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        IsPublic(bool),
+        Visibility(Visibility),
+    }
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::IsPublic(is_public) => is_public.into(),
+        Stored::Visibility(visibility) => visibility,
+    })
+}
+
+impl Visibility {
+    /// Whether anybody, even anonymous users, can see the problem at `now`. `contest` is the
+    /// contest named by `ContestOnly`, if it is not given the problem stays hidden.
+    pub fn is_public(&self, now: &DateTime<Utc>, contest: Option<&Contest>) -> bool {
+        match self {
+            Visibility::Private => false,
+            Visibility::Public => true,
+            Visibility::Scheduled(from) => from <= now,
+            Visibility::ContestOnly { contest: id, reveal_after_end } => {
+                *reveal_after_end
+                    && contest.is_some_and(|contest| contest.id == *id && contest.end_date <= *now)
+            },
+        }
+    }
+}
+
+impl Problem {
+    /// `relations` are the ones `user` holds with the problem and, for `ContestOnly` problems,
+    /// with `contest`. Authors, problem setters and admins always see the problem, contest
+    /// participants see it once the contest starts and the problem is unlocked.
+    pub fn is_visible_to(
+        &self,
+        user: Option<&Uuid>,
+        now: &DateTime<Utc>,
+        relations: &[Relations],
+        contest: Option<&Contest>,
+    ) -> bool {
+        if user == Some(&self.submitted_by)
+            || relations.iter().any(|relation| {
+                matches!(relation, Relations::Owner | Relations::ProblemSetter | Relations::Admin)
+            })
+        {
+            return true;
+        }
+        if self.visibility.is_public(now, contest) {
+            return true;
+        }
+        let Visibility::ContestOnly { contest: id, .. } = &self.visibility else {
+            return false;
+        };
+        user.is_some()
+            && relations.contains(&Relations::Participant)
+            && contest.is_some_and(|contest| {
+                contest.id == *id
+                    && contest.start_date <= *now
+                    && contest
+                        .problems
+                        .iter()
+                        .find(|problem| problem.problem_id == self.id)
+                        .is_some_and(|problem| problem.is_visible(now))
+            })
+    }
+
+    /// Problem list metadata without the submission counters.
+    pub fn meta(&self) -> ProblemMeta {
+        ProblemMeta {
+            tags: self.tags.clone(),
+            difficulty: self.difficulty,
+            source: self.source.clone(),
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Debug, Clone, TS, Validate, Default)]
@@ -287,14 +536,43 @@ pub struct EditablePartsOfProblem {
     pub body: ProblemBody,
     pub checker: Option<Checker>,
     pub validation: ValidationType,
+    /// only for `ValidationType::Interactive`
+    pub interactor: Option<InteractorSpec>,
+    pub validator: Option<Validator>,
+    pub generator_script: Option<GeneratorScript>,
+    pub solutions: Vec<ProblemSolution>,
+    pub tags: Vec<Tag>,
+    pub difficulty: Option<u16>,
+    pub source: Option<String>,
     #[validate(range(min = 256, max = 512))] // memory in mb
     pub memory_limit: u16,
     #[validate(range(min = 1, max = 10))] // time limit in seconds
     pub time_limit: u16,
-    pub is_public: bool,
+    pub visibility: Visibility,
     pub test_cases: Vec<Uuid>,
 }
 
+impl From<&Problem> for EditablePartsOfProblem {
+    fn from(problem: &Problem) -> Self {
+        Self {
+            body: problem.body.clone(),
+            checker: problem.checker.clone(),
+            validation: problem.validation.clone(),
+            interactor: problem.interactor.clone(),
+            validator: problem.validator.clone(),
+            generator_script: problem.generator_script.clone(),
+            solutions: problem.solutions.clone(),
+            tags: problem.tags.clone(),
+            difficulty: problem.difficulty,
+            source: problem.source.clone(),
+            memory_limit: problem.memory_limit,
+            time_limit: problem.time_limit,
+            visibility: problem.visibility.clone(),
+            test_cases: problem.test_cases.clone(),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, TS)]
 #[ts(export)]
 pub struct ProblemGetResponse {
@@ -302,7 +580,7 @@ pub struct ProblemGetResponse {
     pub body: ProblemBody,
     pub memory_limit: u32,
     pub time_limit: u32,
-    pub is_public: bool,
+    pub visibility: Visibility,
     pub author: Uuid
 }
 
@@ -311,35 +589,286 @@ pub struct ProblemGetResponse {
 pub struct ProblemsGetResponse {
     pub problem_id: u32,
     pub body: ProblemBody,
+    pub meta: ProblemMeta,
 }
 
 #[derive(Debug, Validate, Deserialize, TS)]
 #[ts(export)]
+#[validate(schema(function = "validate_problem_form", skip_on_field_errors = false))]
 pub struct ProblemForm {
+    #[validate(custom = "validate_problem_body")]
     pub body: ProblemBody,
-    #[serde(deserialize_with = "empty_string_as_none")]
-    pub checker: Option<String>,
+    #[serde(default)]
+    #[validate]
+    pub checker: Option<CheckerForm>,
     pub validation: ValidationType,
+    #[serde(default)]
+    #[validate]
+    pub interactor: Option<InteractorSpec>,
+    #[serde(default)]
+    #[validate]
+    pub validator: Option<Validator>,
+    #[serde(default)]
+    #[validate]
+    pub generator_script: Option<GeneratorScript>,
+    #[serde(default)]
+    #[validate]
+    pub solutions: Vec<ProblemSolution>,
+    #[serde(default)]
+    #[validate(length(max = "MAX_TAGS_PER_PROBLEM"), custom = "validate_tags")]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    #[validate(range(min = 800, max = 3500))] // codeforces like rating
+    pub difficulty: Option<u16>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[validate(length(max = 100))]
+    pub source: Option<String>,
     #[validate(range(min = 256, max = 512))] // memory in mb
     pub memory_limit: u16,
     #[validate(range(min = 1, max = 10))] // time limit in seconds
     pub time_limit: u16,
-    pub is_public: bool,
+    #[serde(alias = "is_public", deserialize_with = "visibility_or_is_public")]
+    pub visibility: Visibility,
+}
+
+fn validate_problem_form(form: &ProblemForm) -> Result<(), ValidationError> {
+    let names: BTreeSet<_> = form.solutions.iter().map(|solution| &solution.name).collect();
+    if names.len() != form.solutions.len() {
+        return Err(ValidationError::new("duplicated_solution_name"));
+    }
+    match (&form.validation, &form.interactor) {
+        (ValidationType::Interactive, None) => Err(ValidationError::new("missing_interactor")),
+        (ValidationType::Interactive, Some(_)) | (_, None) => Ok(()),
+        (_, Some(_)) => Err(ValidationError::new("interactor_without_interactive_validation")),
+    }
+}
+
+fn validate_tags(tags: &[Tag]) -> Result<(), ValidationError> {
+    let unique: BTreeSet<_> = tags.iter().collect();
+    if unique.len() != tags.len() {
+        return Err(ValidationError::new("duplicated_tag"));
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone, TS)]
+fn validate_problem_body(body: &ProblemBody) -> Result<(), ValidationError> {
+    match find_unsafe_markup_in_body(body) {
+        Some(markup) => {
+            let mut error = ValidationError::new("unsafe_markup");
+            error.add_param("value".into(), &markup);
+            Err(error)
+        },
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, TS, Serialize, Deserialize)]
 #[ts(export)]
 pub struct Checker {
-    pub checker: String,
+    pub kind: CheckerKind,
+    /// `None` for literal checkers, they are not compiled
+    pub language: Option<Language>,
+    pub source: Option<String>,
+    /// sha256 of the compiled checker, set by the evaluator once compiled
+    #[serde(default)]
+    pub artifact_sha256: Option<String>,
+    #[serde(default)]
+    pub compile_status: CheckerCompileStatus,
+}
+
+impl Checker {
+    pub fn testlib(source: String, language: Language) -> Self {
+        Self {
+            kind: CheckerKind::Testlib,
+            language: Some(language),
+            source: Some(source),
+            artifact_sha256: None,
+            compile_status: CheckerCompileStatus::Pending,
+        }
+    }
+
+    pub fn literal(mode: LiteralCheckMode) -> Self {
+        Self {
+            kind: CheckerKind::Literal(mode),
+            language: None,
+            source: None,
+            artifact_sha256: None,
+            compile_status: CheckerCompileStatus::NotRequired,
+        }
+    }
+
+    pub fn needs_compilation(&self) -> bool {
+        self.compile_status == CheckerCompileStatus::Pending
+    }
+
+    /// `ValidationType` the evaluator has to use to run this checker.
+    pub fn validation(&self) -> ValidationType {
+        match self.kind {
+            CheckerKind::Literal(_) => ValidationType::LiteralChecker,
+            CheckerKind::Testlib | CheckerKind::ExitCode | CheckerKind::Kattis => {
+                ValidationType::TestlibChecker
+            },
+        }
+    }
+}
+
+impl From<CheckerForm> for Checker {
+    fn from(form: CheckerForm) -> Self {
+        let compile_status = match form.kind {
+            CheckerKind::Literal(_) => CheckerCompileStatus::NotRequired,
+            _ => CheckerCompileStatus::Pending,
+        };
+        Self {
+            kind: form.kind,
+            language: form.language,
+            source: form.source,
+            artifact_sha256: None,
+            compile_status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, TS, Serialize, Deserialize)]
+#[serde(tag = "type", content = "options", rename_all = "snake_case")]
+#[ts(export)]
+pub enum CheckerKind {
+    /// testlib checker, reads `input output answer` and answers with `TestLibExitCodes`
+    Testlib,
+    /// custom program, exit code 0 accepts and anything else rejects
+    ExitCode,
+    /// Kattis output validator, reads `input answer feedback_dir` with the output on stdin and
+    /// answers with `KattisExitCodes`
+    Kattis,
+    /// no program, outputs are compared with `checker::literal_check`
+    Literal(LiteralCheckMode),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[serde(tag = "status", content = "message", rename_all = "snake_case")]
+#[ts(export)]
+pub enum CheckerCompileStatus {
+    #[default]
+    Pending,
+    NotRequired,
+    Compiled,
+    Failed(String),
 }
 
-#[derive(Debug, Clone, Default, Deserialize, Serialize, TS)]
+#[derive(Debug, Clone, TS, Deserialize, Serialize, Validate)]
+#[ts(export)]
+#[validate(schema(function = "validate_checker_form", skip_on_field_errors = false))]
+pub struct CheckerForm {
+    pub kind: CheckerKind,
+    #[serde(default)]
+    pub language: Option<Language>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[validate(custom = "validate_testlib_source")]
+    pub source: Option<String>,
+}
+
+fn validate_checker_form(form: &CheckerForm) -> Result<(), ValidationError> {
+    match (&form.kind, &form.language, &form.source) {
+        (CheckerKind::Literal(_), None, None) => Ok(()),
+        (CheckerKind::Literal(_), _, _) => Err(ValidationError::new("literal_checker_with_source")),
+        (_, None, _) | (_, _, None) => Err(ValidationError::new("missing_checker_source")),
+        (CheckerKind::Testlib, Some(language), _) => validate_testlib_language(language),
+        (CheckerKind::ExitCode | CheckerKind::Kattis, Some(language), _)
+            if *language != Language::Cmp =>
+        {
+            Ok(())
+        },
+        (_, Some(language), _) => {
+            let mut error = ValidationError::new("unsupported_language");
+            error.add_param("value".into(), &language.to_string());
+            Err(error)
+        },
+    }
+}
+
+/// Sources of testlib programs are limited in bytes, not characters.
+pub(crate) fn validate_testlib_source(source: &str) -> Result<(), ValidationError> {
+    if source.is_empty() || source.len() as u64 > MAX_CHECKER_FILE_SIZE_IN_BYTES {
+        return Err(ValidationError::new("invalid_source_size"));
+    }
+    Ok(())
+}
+
+/// Testlib programs (checkers, interactors and validators) are only built with c++.
+pub(crate) fn validate_testlib_language(language: &Language) -> Result<(), ValidationError> {
+    match language {
+        Language::Cpp11 | Language::Cpp14 | Language::Cpp17 => Ok(()),
+        _ => {
+            let mut error = ValidationError::new("unsupported_language");
+            error.add_param("value".into(), &language.to_string());
+            Err(error)
+        },
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
 #[ts(export)]
 pub struct TestCaseInfo {
     pub stdin_path: String,
     pub stdout_path: String,
     pub problem_id: ProblemId,
     pub id: Uuid,
+    /// position in which the test case is evaluated, starting at 1
+    #[serde(default)]
+    pub ordinal: u32,
+    /// subtask the test case belongs to, `None` for ungrouped tests
+    #[serde(default)]
+    pub group: Option<String>,
+    /// sample tests are shown in the statement as `ProblemExample`
+    #[serde(default)]
+    pub is_sample: bool,
+    #[serde(default)]
+    pub points: Option<u32>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub stdin_metadata: Option<TestCaseFileMetadata>,
+    #[serde(default)]
+    pub stdout_metadata: Option<TestCaseFileMetadata>,
+    /// the input can be rebuilt with the problem `GeneratorScript`
+    #[serde(default)]
+    pub generated_by: Option<GeneratedTest>,
+}
+
+impl TestCaseInfo {
+    /// Checks a regenerated input against the stored one, inputs without metadata can not be
+    /// verified.
+    pub fn verify_generated_input(&self, input: &[u8]) -> Result<(), TestCaseStoreError> {
+        let stored = self
+            .stdin_metadata
+            .as_ref()
+            .ok_or_else(|| TestCaseStoreError::NotFound(self.stdin_path.clone()))?;
+        if TestCaseFileMetadata::from_bytes(input).sha256 != stored.sha256 {
+            return Err(TestCaseStoreError::Corrupted(stored.sha256.clone()));
+        }
+        Ok(())
+    }
+
+    pub fn example(&self) -> Result<ProblemExample> {
+        Ok(ProblemExample {
+            input: std::fs::read_to_string(&self.stdin_path)?,
+            output: std::fs::read_to_string(&self.stdout_path)?,
+        })
+    }
+}
+
+/// Sorts test cases by ordinal and renumbers them so ordinals are consecutive starting at 1.
+pub fn order_test_cases(test_cases: &mut [TestCaseInfo]) {
+    test_cases.sort_by_key(|test_case| test_case.ordinal);
+    for (index, test_case) in test_cases.iter_mut().enumerate() {
+        test_case.ordinal = index as u32 + 1;
+    }
+}
+
+/// Examples shown in the statement, read from the sample tests in evaluation order.
+pub fn examples_from_samples(test_cases: &[TestCaseInfo]) -> Result<Vec<ProblemExample>> {
+    let mut samples: Vec<_> = test_cases.iter().filter(|test_case| test_case.is_sample).collect();
+    samples.sort_by_key(|test_case| test_case.ordinal);
+    samples.into_iter().map(TestCaseInfo::example).collect()
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, Default, TS)]
@@ -369,9 +898,32 @@ pub struct TestCaseResult {
     #[serde(with = "external_struct")]
     pub output: Option<Output>,
     pub duration: Duration,
+    /// only filled for failed sample tests
+    #[serde(default)]
+    pub diff: Option<OutputDiff>,
+    /// only filled for interactive problems
+    #[serde(default)]
+    pub transcript: Option<InteractionTranscript>,
+    /// only filled for run twice problems
+    #[serde(default)]
+    pub first_run: Option<FirstRunResult>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, Default, TS, sqlx::Type)]
+impl TestCaseResult {
+    /// Attaches the diff against `expected` when `info` is a sample the submission got wrong,
+    /// hidden tests never leak their contents. `mode` is the one of the problem's literal
+    /// checker, `Tokens` for checker programs.
+    pub fn attach_diff(&mut self, info: &TestCaseInfo, expected: &[u8], mode: &LiteralCheckMode) {
+        if !info.is_sample || self.status != Status::WrongAnswer {
+            return;
+        }
+        if let Some(output) = &self.output {
+            self.diff = Some(diff(expected, &output.stdout, mode));
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, TS, sqlx::Type)]
 #[ts(export)]
 #[sqlx(type_name = "validation_type", rename_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
@@ -380,6 +932,10 @@ pub enum ValidationType {
     #[default]
     LiteralChecker,
     Interactive,
+    /// there is no program, contestants submit a zip with one output per test case
+    OutputOnly,
+    /// the program runs twice per test case, the second run reads what the first one wrote
+    RunTwice,
 }
 
 impl std::fmt::Display for ValidationType {
@@ -388,10 +944,27 @@ impl std::fmt::Display for ValidationType {
             ValidationType::TestlibChecker => write!(f, "testlib_checker"),
             ValidationType::LiteralChecker => write!(f, "literal_checker"),
             ValidationType::Interactive => write!(f, "interactive"),
+            ValidationType::OutputOnly => write!(f, "output_only"),
+            ValidationType::RunTwice => write!(f, "run_twice"),
         }
     }
 }
 
+/// First of the two runs of a `ValidationType::RunTwice` test case, the second one is the
+/// `TestCaseResult` itself.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct FirstRunResult {
+    pub status: Status,
+    #[serde(with = "external_struct")]
+    pub output: Option<Output>,
+    pub duration: Duration,
+}
+
+/// Shape of the results depends on `validation`:
+///
+/// - `OutputOnly`: `prepare_output` is `None`, test cases have no `output` and a zero
+///   `duration`, a test case without a submitted output is a `WrongAnswer`.
+/// - `RunTwice`: every test case has a `first_run`, the overall duration adds both runs.
 #[derive(Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 pub struct ProblemExecutorResult {
     pub overall_result: Status,
@@ -399,16 +972,27 @@ pub struct ProblemExecutorResult {
     pub total_duration: Duration,
     #[serde(with = "external_struct")]
     pub prepare_output: Option<Output>,
+    #[serde(default)]
+    pub validation: ValidationType,
 }
 #[cfg(test)]
 mod tests {
     use std::time::{SystemTime, UNIX_EPOCH};
 
+    use chrono::{DateTime, Utc};
     use rand::Rng;
+    use serde_json::json;
+    use uuid::Uuid;
+    use validator::Validate;
 
     use crate::{
-        consts::{CONTEST_ID_BITS, PROBLEM_ID_BITS},
-        problem::{ContestId, ProblemId, SubmissionId},
+        consts::{CONTEST_ID_BITS, MAX_CHECKER_FILE_SIZE_IN_BYTES, PROBLEM_ID_BITS},
+        contest::{Contest, ContestBody, ContestProblem, ContestType, RegistrationPolicy},
+        problem::{
+            CheckerForm, ContestId, Problem, ProblemBody, ProblemForm, ProblemId, SubmissionId,
+            Visibility,
+        },
+        relations::Relations,
     };
 
     #[test]
@@ -438,4 +1022,162 @@ mod tests {
         let bytes: [u8; 16] = id.as_bit_vec().to_owned().to_bytes().try_into().unwrap();
         assert_eq!(id.as_u128(), u128::from_be_bytes(bytes));
     }
+
+    #[test]
+    fn problem_body_resolves_locales_with_fallback() {
+        let body: ProblemBody = serde_json::from_value(json!({
+            "information": "",
+            "identifier": "",
+            "name": "Suma",
+            "input": "Dos enteros",
+            "output": "Su suma",
+            "problem": "Suma dos enteros",
+            "note": "",
+            "examples": [],
+            "translations": {
+                "en": {
+                    "name": "Sum",
+                    "information": "",
+                    "input": "Two integers",
+                    "output": "Their sum",
+                    "problem": "Add two integers"
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(body.locale.0, "es");
+        assert_eq!(body.localized("es").name, "Suma");
+        assert_eq!(body.localized("en").name, "Sum");
+        assert_eq!(body.localized("en-US").name, "Sum");
+        assert_eq!(body.localized("fr").name, "Suma");
+    }
+
+    #[test]
+    fn checker_form_requires_a_compatible_source() {
+        let form = |value| serde_json::from_value::<CheckerForm>(value).unwrap().validate();
+
+        assert!(
+            form(json!({ "kind": { "type": "literal", "options": { "mode": "tokens" } } })).is_ok()
+        );
+        assert!(form(json!({ "kind": { "type": "testlib" }, "language": "cpp17", "source": "" }))
+            .is_err());
+        assert!(form(json!({ "kind": { "type": "testlib" }, "language": "java", "source": "x" }))
+            .is_err());
+        assert!(form(
+            json!({ "kind": { "type": "exit_code" }, "language": "java", "source": "x" })
+        )
+        .is_ok());
+
+        // the limit is in bytes, a source of two byte characters counts twice
+        let limit = MAX_CHECKER_FILE_SIZE_IN_BYTES as usize;
+        let source = "é".repeat(limit / 2 + 1);
+        assert!(source.chars().count() <= limit);
+        assert!(form(
+            json!({ "kind": { "type": "testlib" }, "language": "cpp17", "source": source })
+        )
+        .is_err());
+        let source = "é".repeat(limit / 2);
+        assert!(form(
+            json!({ "kind": { "type": "testlib" }, "language": "cpp17", "source": source })
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn problem_form_rejects_duplicated_solution_names() {
+        let form = |names: [&str; 2]| {
+            serde_json::from_value::<ProblemForm>(json!({
+                "body": {
+                    "information": "",
+                    "identifier": "",
+                    "name": "Suma",
+                    "input": "Dos enteros",
+                    "output": "Su suma",
+                    "problem": "Suma dos enteros",
+                    "note": "",
+                    "examples": []
+                },
+                "validation": "literal_checker",
+                "solutions": names.map(|name| json!({
+                    "name": name,
+                    "language": "cpp17",
+                    "source": "int main() {}",
+                    "expected": "accepted"
+                })),
+                "memory_limit": 256,
+                "time_limit": 1,
+                "visibility": { "type": "public" }
+            }))
+            .unwrap()
+            .validate()
+        };
+
+        assert!(form(["accepted/sol.cpp", "wrong_answer/sol.cpp"]).is_ok());
+        assert!(form(["sol.cpp", "sol.cpp"]).is_err());
+    }
+
+    #[test]
+    fn contest_only_problems_are_revealed_after_the_contest() {
+        let now = Utc::now();
+        let problem = Problem {
+            id: ProblemId(7),
+            submitted_by: Uuid::new_v4(),
+            visibility: Visibility::ContestOnly { contest: ContestId(1), reveal_after_end: true },
+            ..Default::default()
+        };
+        let contest = |start_date: DateTime<Utc>| Contest {
+            id: ContestId(1),
+            author: Uuid::new_v4(),
+            body: ContestBody::default(),
+            name: "Concurso".to_string(),
+            start_date,
+            end_date: start_date + chrono::Duration::hours(5),
+            contest_type: ContestType::ICPC,
+            problems: ContestProblem::from_problem_ids(vec![ProblemId(7)]),
+            is_frozen: false,
+            frozen_time: 0,
+            registration_policy: RegistrationPolicy::Open,
+            registration_start: None,
+            registration_end: None,
+            registration_password_hash: None,
+        };
+        let user = Uuid::new_v4();
+        let upcoming = contest(now + chrono::Duration::hours(1));
+        let running = contest(now - chrono::Duration::hours(1));
+        let ended = contest(now - chrono::Duration::days(1));
+        let participant = [Relations::Participant];
+
+        assert!(problem.is_visible_to(Some(&problem.submitted_by), &now, &[], None));
+        assert!(problem.is_visible_to(Some(&user), &now, &[Relations::ProblemSetter], None));
+        assert!(!problem.is_visible_to(Some(&user), &now, &participant, Some(&upcoming)));
+        assert!(problem.is_visible_to(Some(&user), &now, &participant, Some(&running)));
+        assert!(!problem.is_visible_to(Some(&user), &now, &[], Some(&running)));
+        assert!(problem.is_visible_to(None, &now, &[], Some(&ended)));
+
+        let scheduled = Visibility::Scheduled(now + chrono::Duration::minutes(1));
+        assert!(!scheduled.is_public(&now, None));
+        assert!(scheduled.is_public(&(now + chrono::Duration::minutes(2)), None));
+    }
+
+    #[test]
+    fn problems_with_the_old_is_public_flag_are_read() {
+        let stored = |is_public| {
+            let mut problem = serde_json::to_value(Problem::default()).unwrap();
+            let fields = problem.as_object_mut().unwrap();
+            fields.remove("visibility");
+            fields.insert("is_public".to_string(), json!(is_public));
+            serde_json::from_value::<Problem>(problem).unwrap().visibility
+        };
+        assert_eq!(stored(true), Visibility::Public);
+        assert_eq!(stored(false), Visibility::Private);
+
+        let problem = Problem {
+            visibility: Visibility::ContestOnly { contest: ContestId(1), reveal_after_end: false },
+            ..Default::default()
+        };
+        let read: Problem =
+            serde_json::from_value(serde_json::to_value(&problem).unwrap()).unwrap();
+        assert_eq!(read.visibility, problem.visibility);
+    }
 }
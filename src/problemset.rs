@@ -0,0 +1,308 @@
+//! Metadata used to browse the problemset: tags, difficulty and the filters of the problem
+//! list.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    consts::{DEFAULT_PROBLEMS_PER_PAGE, MAX_PROBLEMS_PER_PAGE},
+    problem::ProblemBody,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize, TS)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+pub enum Tag {
+    Implementation,
+    Math,
+    Greedy,
+    DynamicProgramming,
+    DataStructures,
+    BruteForce,
+    ConstructiveAlgorithms,
+    Graphs,
+    Trees,
+    ShortestPaths,
+    Flows,
+    Strings,
+    Hashing,
+    NumberTheory,
+    Combinatorics,
+    Probabilities,
+    Geometry,
+    BinarySearch,
+    TwoPointers,
+    Sortings,
+    Bitmasks,
+    DivideAndConquer,
+    Games,
+    Interactive,
+}
+
+impl Tag {
+    /// The whole vocabulary, in the order it is shown to users.
+    pub const ALL: [Tag; 24] = [
+        Tag::Implementation,
+        Tag::Math,
+        Tag::Greedy,
+        Tag::DynamicProgramming,
+        Tag::DataStructures,
+        Tag::BruteForce,
+        Tag::ConstructiveAlgorithms,
+        Tag::Graphs,
+        Tag::Trees,
+        Tag::ShortestPaths,
+        Tag::Flows,
+        Tag::Strings,
+        Tag::Hashing,
+        Tag::NumberTheory,
+        Tag::Combinatorics,
+        Tag::Probabilities,
+        Tag::Geometry,
+        Tag::BinarySearch,
+        Tag::TwoPointers,
+        Tag::Sortings,
+        Tag::Bitmasks,
+        Tag::DivideAndConquer,
+        Tag::Games,
+        Tag::Interactive,
+    ];
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            Tag::Implementation => "implementation",
+            Tag::Math => "math",
+            Tag::Greedy => "greedy",
+            Tag::DynamicProgramming => "dynamic_programming",
+            Tag::DataStructures => "data_structures",
+            Tag::BruteForce => "brute_force",
+            Tag::ConstructiveAlgorithms => "constructive_algorithms",
+            Tag::Graphs => "graphs",
+            Tag::Trees => "trees",
+            Tag::ShortestPaths => "shortest_paths",
+            Tag::Flows => "flows",
+            Tag::Strings => "strings",
+            Tag::Hashing => "hashing",
+            Tag::NumberTheory => "number_theory",
+            Tag::Combinatorics => "combinatorics",
+            Tag::Probabilities => "probabilities",
+            Tag::Geometry => "geometry",
+            Tag::BinarySearch => "binary_search",
+            Tag::TwoPointers => "two_pointers",
+            Tag::Sortings => "sortings",
+            Tag::Bitmasks => "bitmasks",
+            Tag::DivideAndConquer => "divide_and_conquer",
+            Tag::Games => "games",
+            Tag::Interactive => "interactive",
+        };
+        write!(f, "{}", tag)
+    }
+}
+
+impl TryFrom<String> for Tag {
+    type Error = &'static str;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Tag::ALL.into_iter().find(|tag| tag.to_string() == value).ok_or("Invalid tag")
+    }
+}
+
+/// What the problem list shows besides the statement, the counters are computed from the
+/// accepted submissions.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct ProblemMeta {
+    pub tags: Vec<Tag>,
+    /// codeforces like rating
+    pub difficulty: Option<u16>,
+    /// where the problem was first used, e.g. a contest name
+    pub source: Option<String>,
+    /// users with at least one accepted submission
+    pub solved_count: u32,
+    /// accepted submissions over judged submissions, `None` when nothing was judged
+    pub acceptance_rate: Option<f32>,
+}
+
+impl ProblemMeta {
+    pub fn with_stats(mut self, solved_count: u32, accepted: u64, judged: u64) -> Self {
+        self.solved_count = solved_count;
+        self.acceptance_rate = (judged > 0).then(|| accepted as f32 / judged as f32);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum TagMatch {
+    /// problems having every tag
+    #[default]
+    All,
+    /// problems having at least one of the tags
+    Any,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS, Validate)]
+#[ts(export)]
+#[validate(schema(function = "validate_problem_query", skip_on_field_errors = false))]
+pub struct ProblemQuery {
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub tag_match: TagMatch,
+    #[serde(default)]
+    pub min_difficulty: Option<u16>,
+    #[serde(default)]
+    pub max_difficulty: Option<u16>,
+    #[serde(default)]
+    pub author: Option<Uuid>,
+    /// searched in the problem name of every locale, ignoring case
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub text: Option<String>,
+    /// starting at 1
+    #[serde(default = "first_page")]
+    #[validate(range(min = 1))]
+    pub page: u32,
+    #[serde(default = "default_per_page")]
+    #[validate(range(min = 1, max = "MAX_PROBLEMS_PER_PAGE"))]
+    pub per_page: u32,
+}
+
+fn first_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    DEFAULT_PROBLEMS_PER_PAGE
+}
+
+fn validate_problem_query(query: &ProblemQuery) -> Result<(), ValidationError> {
+    match (query.min_difficulty, query.max_difficulty) {
+        (Some(min), Some(max)) if min > max => {
+            Err(ValidationError::new("invalid_difficulty_range"))
+        },
+        _ => Ok(()),
+    }
+}
+
+impl Default for ProblemQuery {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            tag_match: TagMatch::default(),
+            min_difficulty: None,
+            max_difficulty: None,
+            author: None,
+            text: None,
+            page: first_page(),
+            per_page: default_per_page(),
+        }
+    }
+}
+
+impl ProblemQuery {
+    pub fn offset(&self) -> u64 {
+        self.page.saturating_sub(1) as u64 * self.per_page as u64
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.per_page as u64
+    }
+
+    pub fn matches(&self, body: &ProblemBody, meta: &ProblemMeta, author: &Uuid) -> bool {
+        let tags = match self.tag_match {
+            TagMatch::All => self.tags.iter().all(|tag| meta.tags.contains(tag)),
+            TagMatch::Any => {
+                self.tags.is_empty() || self.tags.iter().any(|tag| meta.tags.contains(tag))
+            },
+        };
+        let difficulty = match (self.min_difficulty, self.max_difficulty) {
+            (None, None) => true,
+            (min, max) => meta.difficulty.is_some_and(|difficulty| {
+                min.is_none_or(|min| difficulty >= min) && max.is_none_or(|max| difficulty <= max)
+            }),
+        };
+        let text = self.text.as_ref().is_none_or(|text| {
+            let text = text.to_lowercase();
+            std::iter::once(&body.name)
+                .chain(body.translations.values().map(|statement| &statement.name))
+                .any(|name| name.to_lowercase().contains(&text))
+        });
+        tags && difficulty && text && self.author.as_ref().is_none_or(|other| other == author)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: u32,
+    pub per_page: u32,
+    /// items matching the query across every page
+    #[ts(type = "number")]
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    pub fn new(query: &ProblemQuery, items: Vec<T>, total: u64) -> Self {
+        Self { items, page: query.page, per_page: query.per_page, total }
+    }
+
+    pub fn pages(&self) -> u64 {
+        self.total.div_ceil(self.per_page.max(1) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+    use validator::Validate;
+
+    use super::{ProblemMeta, ProblemQuery, Tag, TagMatch};
+    use crate::problem::ProblemBody;
+
+    #[test]
+    fn query_filters_by_tags_difficulty_and_text() {
+        let author = Uuid::new_v4();
+        let body = ProblemBody { name: "Camino Más Corto".to_string(), ..Default::default() };
+        let meta = ProblemMeta {
+            tags: vec![Tag::Graphs, Tag::ShortestPaths],
+            difficulty: Some(1600),
+            ..Default::default()
+        };
+        let query = |value| serde_json::from_value::<ProblemQuery>(value).unwrap();
+
+        assert!(query(json!({})).matches(&body, &meta, &author));
+        assert!(query(json!({ "tags": ["graphs", "shortest_paths"], "text": "más corto" }))
+            .matches(&body, &meta, &author));
+        assert!(!query(json!({ "tags": ["graphs", "dynamic_programming"] }))
+            .matches(&body, &meta, &author));
+        assert!(query(json!({ "tags": ["graphs", "dynamic_programming"], "tag_match": "any" }))
+            .matches(&body, &meta, &author));
+        assert!(!query(json!({ "min_difficulty": 1700 })).matches(&body, &meta, &author));
+        assert!(!query(json!({ "author": Uuid::new_v4() })).matches(&body, &meta, &author));
+
+        let page = query(json!({ "page": 3, "per_page": 10 }));
+        assert_eq!((page.offset(), page.limit()), (20, 10));
+        assert_eq!(query(json!({})).tag_match, TagMatch::All);
+        assert!(query(json!({ "min_difficulty": 2000, "max_difficulty": 1000 }))
+            .validate()
+            .is_err());
+        assert!(query(json!({ "per_page": 1000 })).validate().is_err());
+    }
+
+    #[test]
+    fn tags_round_trip_through_strings() {
+        for tag in Tag::ALL {
+            assert_eq!(Tag::try_from(tag.to_string()), Ok(tag));
+            assert_eq!(serde_json::to_value(tag).unwrap(), json!(tag.to_string()));
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use chrono::{
+    serde::{ts_milliseconds, ts_milliseconds_option},
+    DateTime, Utc,
+};
+use openssl::{error::ErrorStack, hash::MessageDigest, memcmp, pkcs5::pbkdf2_hmac};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    consts::{
+        INVITATION_TOKEN_SIZE, REGISTRATION_PASSWORD_HASH_ITERATIONS,
+        REGISTRATION_PASSWORD_SALT_SIZE,
+    },
+    problem::ContestId,
+    relations::Resource,
+    utils::empty_string_as_none,
+};
+
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, TS, sqlx::Type)]
+#[ts(export)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "registration_status", rename_all = "snake_case")]
+pub enum RegistrationStatus {
+    #[default]
+    Pending,
+    Registered,
+    Rejected,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS, Validate)]
+#[ts(export)]
+pub struct RegistrationRequest {
+    pub contest_id: u32,
+    /// register as a team instead of individually
+    #[serde(default)]
+    pub team_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[validate(length(max = 64))]
+    pub password: Option<String>,
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    #[validate(length(equal = "INVITATION_TOKEN_SIZE"))]
+    pub invitation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct RegistrationResponse {
+    pub contest_id: u32,
+    pub status: RegistrationStatus,
+    pub message: Option<String>,
+}
+
+/// Single use (or limited use) token granting access to an invite only contest.
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct Invitation {
+    pub token: String,
+    pub contest_id: ContestId,
+    pub created_by: Uuid,
+    /// the invitation can be redeemed by anyone holding the token when `None`
+    pub invitee: Option<Uuid>,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub created_at: DateTime<Utc>,
+    #[serde(default, with = "ts_milliseconds_option")]
+    #[ts(type = "number | null")]
+    pub expires_at: Option<DateTime<Utc>>,
+    pub max_uses: Option<u32>,
+    pub uses: u32,
+}
+
+impl Invitation {
+    pub fn new(contest_id: ContestId, created_by: Uuid) -> Self {
+        Self {
+            token: Self::generate_token(),
+            contest_id,
+            created_by,
+            invitee: None,
+            created_at: Utc::now(),
+            expires_at: None,
+            max_uses: Some(1),
+            uses: 0,
+        }
+    }
+
+    pub fn generate_token() -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(INVITATION_TOKEN_SIZE as usize)
+            .map(char::from)
+            .collect()
+    }
+
+    pub fn resource(&self) -> Resource {
+        Resource::Contest(self.contest_id.clone())
+    }
+
+    pub fn can_be_redeemed_by(&self, user_id: &Uuid, now: &DateTime<Utc>) -> bool {
+        self.invitee.as_ref().is_none_or(|invitee| invitee == user_id)
+            && self.expires_at.is_none_or(|expires_at| *now < expires_at)
+            && self.max_uses.is_none_or(|max_uses| self.uses < max_uses)
+    }
+}
+
+const PASSWORD_HASH_SCHEME: &str = "pbkdf2_sha256";
+
+/// Hashes the password of a password protected contest as
+/// `pbkdf2_sha256$<iterations>$<salt>$<hash>`, salt and hash in hex.
+pub fn hash_registration_password(password: &str) -> Result<String, ErrorStack> {
+    let salt: [u8; REGISTRATION_PASSWORD_SALT_SIZE] = rand::thread_rng().gen();
+    let hash = pbkdf2(password, &salt, REGISTRATION_PASSWORD_HASH_ITERATIONS)?;
+    Ok(format!(
+        "{}${}${}${}",
+        PASSWORD_HASH_SCHEME,
+        REGISTRATION_PASSWORD_HASH_ITERATIONS,
+        hex::encode(salt),
+        hex::encode(hash)
+    ))
+}
+
+/// Whether `password` matches a hash made by `hash_registration_password`, malformed hashes
+/// match nothing.
+pub fn verify_registration_password(password: &str, hash: &str) -> bool {
+    let mut parts = hash.split('$');
+    let (Some(PASSWORD_HASH_SCHEME), Some(iterations), Some(salt), Some(expected), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Ok(iterations), Ok(salt), Ok(expected)) =
+        (iterations.parse(), hex::decode(salt), hex::decode(expected))
+    else {
+        return false;
+    };
+    pbkdf2(password, &salt, iterations)
+        .is_ok_and(|hash| hash.len() == expected.len() && memcmp::eq(&hash, &expected))
+}
+
+fn pbkdf2(password: &str, salt: &[u8], iterations: usize) -> Result<[u8; 32], ErrorStack> {
+    let mut hash = [0; 32];
+    pbkdf2_hmac(password.as_bytes(), salt, iterations, MessageDigest::sha256(), &mut hash)?;
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{hash_registration_password, verify_registration_password};
+
+    #[test]
+    fn registration_password_is_hashed_and_verified() {
+        let hash = hash_registration_password("acatlan").unwrap();
+
+        assert!(hash.starts_with("pbkdf2_sha256$"));
+        assert!(!hash.contains("acatlan"));
+        assert_ne!(hash, hash_registration_password("acatlan").unwrap());
+        assert!(verify_registration_password("acatlan", &hash));
+        assert!(!verify_registration_password("Acatlan", &hash));
+        assert!(!verify_registration_password("acatlan", "acatlan"));
+    }
+}
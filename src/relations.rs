@@ -31,6 +31,7 @@ pub enum Resource {
     Problem(ProblemId),
     Submission(SubmissionId),
     User(Uuid),
+    Team(Uuid),
 }
 
 impl fmt::Display for Resource {
@@ -39,7 +40,8 @@ impl fmt::Display for Resource {
             Resource::Contest(id) => write!(f,"problem:{}", id.as_u32()),
             Resource::Problem(id) => write!(f,"contest:{}", id.as_u32()),
             Resource::Submission(id) => write!(f,"submission:{}", id.as_u128()),
-            Resource::User(id) => write!(f,"user:{}", id.to_string()),
+            Resource::User(id) => write!(f,"user:{}", id),
+            Resource::Team(id) => write!(f,"team:{}", id),
         }
     }
 }
@@ -0,0 +1,188 @@
+use ammonia::Builder;
+use lazy_static::lazy_static;
+use pulldown_cmark::{html, Options, Parser};
+
+use crate::problem::{ProblemBody, ProblemStatement, StatementFormat};
+
+/// Tags that are never accepted in a statement, even if the sanitizer would strip them.
+pub const FORBIDDEN_TAGS: [&str; 7] = [
+    "script", "iframe", "object", "embed", "frame", "frameset", "style",
+];
+
+lazy_static! {
+    static ref SANITIZER: Builder<'static> = {
+        let mut builder = Builder::default();
+        builder
+            .add_allowed_classes("span", ["math", "math-inline", "math-display"])
+            .add_allowed_classes("div", ["latex"]);
+        builder
+    };
+}
+
+/// Renders a statement field to html that is safe to inject in the frontend.
+///
+/// Math is not rendered here, it is left inside `span.math` (markdown) or `div.latex` (latex)
+/// for the frontend to typeset.
+pub fn render_statement(text: &str, format: &StatementFormat) -> String {
+    let html = match format {
+        StatementFormat::Markdown => {
+            let options =
+                Options::ENABLE_MATH | Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+            let mut html = String::with_capacity(text.len() * 3 / 2);
+            html::push_html(&mut html, Parser::new_ext(text, options));
+            html
+        },
+        StatementFormat::Html => text.to_string(),
+        StatementFormat::Latex => format!(r#"<div class="latex">{}</div>"#, escape_html(text)),
+        StatementFormat::PlainText => format!("<pre>{}</pre>", escape_html(text)),
+    };
+    SANITIZER.clean(&html).to_string()
+}
+
+pub fn render_problem_statement(
+    statement: &ProblemStatement,
+    format: &StatementFormat,
+) -> ProblemStatement {
+    ProblemStatement {
+        name: ammonia::clean_text(&statement.name),
+        information: render_statement(&statement.information, format),
+        input: render_statement(&statement.input, format),
+        output: render_statement(&statement.output, format),
+        problem: render_statement(&statement.problem, format),
+        note: statement
+            .note
+            .as_ref()
+            .map(|note| render_statement(note, format)),
+    }
+}
+
+/// Returns the first forbidden construct found in `text`: a forbidden tag, an inline event
+/// handler (`onerror=...`) or a `javascript:` url.
+pub fn find_unsafe_markup(text: &str) -> Option<String> {
+    let text = text.to_ascii_lowercase();
+    if text.contains("javascript:") {
+        return Some("javascript:".to_string());
+    }
+    for tag in text.split('<').skip(1) {
+        let tag = tag
+            .split('>')
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches('/');
+        let name = tag
+            .split(|c: char| c.is_ascii_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default();
+        if FORBIDDEN_TAGS.contains(&name) {
+            return Some(name.to_string());
+        }
+        if let Some(handler) = find_event_handler(&tag[name.len()..]) {
+            return Some(handler);
+        }
+    }
+    None
+}
+
+pub fn find_unsafe_markup_in_body(body: &ProblemBody) -> Option<String> {
+    std::iter::once(body.statement())
+        .chain(body.translations.values().cloned())
+        .flat_map(|statement| {
+            [
+                Some(statement.name),
+                Some(statement.information),
+                Some(statement.input),
+                Some(statement.output),
+                Some(statement.problem),
+                statement.note,
+            ]
+        })
+        .flatten()
+        .find_map(|field| find_unsafe_markup(&field))
+}
+
+fn find_event_handler(attributes: &str) -> Option<String> {
+    let bytes = attributes.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let starts_word = i == 0
+            || matches!(bytes[i - 1], b'/' | b'"' | b'\'')
+            || bytes[i - 1].is_ascii_whitespace();
+        if !(starts_word && bytes[i].is_ascii_alphabetic()) {
+            i += 1;
+            continue;
+        }
+        let end = i + bytes[i..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphabetic())
+            .count();
+        let name = &attributes[i..end];
+        if name.len() > 2
+            && name.starts_with("on")
+            && attributes[end..].trim_start().starts_with('=')
+        {
+            return Some(name.to_string());
+        }
+        i = end;
+    }
+    None
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        problem::StatementFormat,
+        render::{find_unsafe_markup, render_statement},
+    };
+
+    #[test]
+    fn markdown_is_rendered_and_sanitized() {
+        let html = render_statement(
+            "**Suma** $a + b$ <img src=x onerror=alert(1)>",
+            &StatementFormat::Markdown,
+        );
+        assert!(html.contains("<strong>Suma</strong>"));
+        assert!(html.contains(r#"<span class="math math-inline">a + b</span>"#));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn unsafe_markup_is_detected() {
+        assert_eq!(
+            find_unsafe_markup("<SCRIPT>alert(1)</script>"),
+            Some("script".to_string())
+        );
+        assert_eq!(
+            find_unsafe_markup("<iframe src=x>"),
+            Some("iframe".to_string())
+        );
+        assert_eq!(
+            find_unsafe_markup("<img src=x onerror = alert(1)>"),
+            Some("onerror".to_string())
+        );
+        assert_eq!(
+            find_unsafe_markup("[x](javascript:alert(1))"),
+            Some("javascript:".to_string())
+        );
+        assert_eq!(find_unsafe_markup("si $a<b$ y $b>c$ entonces $a<c$"), None);
+        assert_eq!(find_unsafe_markup("<img src=\"online.png\">"), None);
+        assert_eq!(
+            find_unsafe_markup("</é> </ñandú onclick=x>"),
+            Some("onclick".to_string())
+        );
+    }
+}
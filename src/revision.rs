@@ -0,0 +1,360 @@
+//! Problem revisions, every edit of a problem bumps its revision so submissions can be judged
+//! and rejudged against the version they were sent to.
+use chrono::{serde::ts_milliseconds, DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    interactor::InteractorSpec,
+    problem::{
+        Checker, EditablePartsOfProblem, Locale, ProblemBody, ProblemId, ValidationType, Visibility,
+    },
+    problemset::Tag,
+    solution::ProblemSolution,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+#[ts(export)]
+pub enum ProblemChange {
+    /// locales whose statement was added, removed or edited
+    Statement {
+        locales: Vec<Locale>,
+    },
+    Examples,
+    Checker,
+    Validation {
+        from: ValidationType,
+        to: ValidationType,
+    },
+    Interactor,
+    Validator,
+    GeneratorScript,
+    Solutions {
+        added: Vec<String>,
+        removed: Vec<String>,
+        modified: Vec<String>,
+    },
+    Tags {
+        added: Vec<Tag>,
+        removed: Vec<Tag>,
+    },
+    Difficulty {
+        from: Option<u16>,
+        to: Option<u16>,
+    },
+    Source {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    MemoryLimit {
+        from: u16,
+        to: u16,
+    },
+    TimeLimit {
+        from: u16,
+        to: u16,
+    },
+    Visibility {
+        from: Visibility,
+        to: Visibility,
+    },
+    TestCases {
+        added: Vec<Uuid>,
+        removed: Vec<Uuid>,
+        reordered: bool,
+    },
+}
+
+impl ProblemChange {
+    /// Whether submissions judged before the change could get a different verdict after it.
+    pub fn affects_verdicts(&self) -> bool {
+        matches!(
+            self,
+            ProblemChange::Checker
+                | ProblemChange::Validation { .. }
+                | ProblemChange::Interactor
+                | ProblemChange::MemoryLimit { .. }
+                | ProblemChange::TimeLimit { .. }
+                | ProblemChange::TestCases { .. }
+        )
+    }
+}
+
+/// Parts of a problem that decide verdicts, as they were at one revision.
+#[derive(Debug, Clone, Default, PartialEq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct JudgingSnapshot {
+    pub checker: Option<Checker>,
+    pub validation: ValidationType,
+    pub interactor: Option<InteractorSpec>,
+    pub memory_limit: u16,
+    pub time_limit: u16,
+    /// in judging order
+    pub test_cases: Vec<Uuid>,
+}
+
+impl From<&EditablePartsOfProblem> for JudgingSnapshot {
+    fn from(problem: &EditablePartsOfProblem) -> Self {
+        Self {
+            checker: problem.checker.clone(),
+            validation: problem.validation.clone(),
+            interactor: problem.interactor.clone(),
+            memory_limit: problem.memory_limit,
+            time_limit: problem.time_limit,
+            test_cases: problem.test_cases.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct ProblemRevision {
+    pub problem_id: ProblemId,
+    /// starts at 1 when the problem is created
+    pub revision: u32,
+    #[serde(with = "ts_milliseconds")]
+    #[ts(type = "number")]
+    pub created_at: DateTime<Utc>,
+    pub author: Uuid,
+    /// empty for the first revision
+    pub changes: Vec<ProblemChange>,
+    pub snapshot: JudgingSnapshot,
+}
+
+impl ProblemRevision {
+    pub fn first(
+        problem_id: ProblemId,
+        problem: &EditablePartsOfProblem,
+        author: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            problem_id,
+            revision: 1,
+            created_at,
+            author,
+            changes: Vec::new(),
+            snapshot: problem.into(),
+        }
+    }
+
+    /// Revision following `self` after editing `old` into `new`, `None` when nothing changed.
+    pub fn next(
+        &self,
+        old: &EditablePartsOfProblem,
+        new: &EditablePartsOfProblem,
+        author: Uuid,
+        created_at: DateTime<Utc>,
+    ) -> Option<Self> {
+        let changes = diff_problems(old, new);
+        (!changes.is_empty()).then(|| Self {
+            problem_id: self.problem_id.clone(),
+            revision: self.revision + 1,
+            created_at,
+            author,
+            changes,
+            snapshot: new.into(),
+        })
+    }
+
+    /// `current` with the parts that decide verdicts as they were at this revision, what
+    /// submissions sent to this revision are rejudged against.
+    pub fn rebuild(&self, current: &EditablePartsOfProblem) -> EditablePartsOfProblem {
+        let snapshot = self.snapshot.clone();
+        EditablePartsOfProblem {
+            checker: snapshot.checker,
+            validation: snapshot.validation,
+            interactor: snapshot.interactor,
+            memory_limit: snapshot.memory_limit,
+            time_limit: snapshot.time_limit,
+            test_cases: snapshot.test_cases,
+            ..current.clone()
+        }
+    }
+
+    pub fn requires_rejudge(&self) -> bool {
+        self.changes.iter().any(ProblemChange::affects_verdicts)
+    }
+}
+
+/// Changes needed to turn `old` into `new`, in field order.
+pub fn diff_problems(
+    old: &EditablePartsOfProblem,
+    new: &EditablePartsOfProblem,
+) -> Vec<ProblemChange> {
+    let mut changes = Vec::new();
+    let locales = changed_locales(&old.body, &new.body);
+    if !locales.is_empty() {
+        changes.push(ProblemChange::Statement { locales });
+    }
+    if old.body.examples != new.body.examples {
+        changes.push(ProblemChange::Examples);
+    }
+    if old.checker != new.checker {
+        changes.push(ProblemChange::Checker);
+    }
+    if old.validation != new.validation {
+        changes.push(ProblemChange::Validation {
+            from: old.validation.clone(),
+            to: new.validation.clone(),
+        });
+    }
+    if old.interactor != new.interactor {
+        changes.push(ProblemChange::Interactor);
+    }
+    if old.validator != new.validator {
+        changes.push(ProblemChange::Validator);
+    }
+    if old.generator_script != new.generator_script {
+        changes.push(ProblemChange::GeneratorScript);
+    }
+
+    let names = |solutions: &[ProblemSolution]| {
+        solutions.iter().map(|solution| solution.name.clone()).collect::<Vec<_>>()
+    };
+    let (added, removed) = added_and_removed(&names(&old.solutions), &names(&new.solutions));
+    let modified: Vec<_> = new
+        .solutions
+        .iter()
+        .filter(|solution| {
+            old.solutions.iter().any(|old| old.name == solution.name && old != *solution)
+        })
+        .map(|solution| solution.name.clone())
+        .collect();
+    if !added.is_empty() || !removed.is_empty() || !modified.is_empty() {
+        changes.push(ProblemChange::Solutions { added, removed, modified });
+    }
+
+    let (added, removed) = added_and_removed(&old.tags, &new.tags);
+    if !added.is_empty() || !removed.is_empty() {
+        changes.push(ProblemChange::Tags { added, removed });
+    }
+    if old.difficulty != new.difficulty {
+        changes.push(ProblemChange::Difficulty { from: old.difficulty, to: new.difficulty });
+    }
+    if old.source != new.source {
+        changes.push(ProblemChange::Source { from: old.source.clone(), to: new.source.clone() });
+    }
+    if old.memory_limit != new.memory_limit {
+        changes.push(ProblemChange::MemoryLimit { from: old.memory_limit, to: new.memory_limit });
+    }
+    if old.time_limit != new.time_limit {
+        changes.push(ProblemChange::TimeLimit { from: old.time_limit, to: new.time_limit });
+    }
+    if old.visibility != new.visibility {
+        changes.push(ProblemChange::Visibility {
+            from: old.visibility.clone(),
+            to: new.visibility.clone(),
+        });
+    }
+
+    let (added, removed) = added_and_removed(&old.test_cases, &new.test_cases);
+    let kept = |test_cases: &[Uuid]| -> Vec<Uuid> {
+        test_cases
+            .iter()
+            .filter(|id| !added.contains(id) && !removed.contains(id))
+            .copied()
+            .collect()
+    };
+    let reordered = kept(&old.test_cases) != kept(&new.test_cases);
+    if !added.is_empty() || !removed.is_empty() || reordered {
+        changes.push(ProblemChange::TestCases { added, removed, reordered });
+    }
+    changes
+}
+
+fn added_and_removed<T: Clone + PartialEq>(old: &[T], new: &[T]) -> (Vec<T>, Vec<T>) {
+    let added = new.iter().filter(|item| !old.contains(item)).cloned().collect();
+    let removed = old.iter().filter(|item| !new.contains(item)).cloned().collect();
+    (added, removed)
+}
+
+fn changed_locales(old: &ProblemBody, new: &ProblemBody) -> Vec<Locale> {
+    let mut locales: Vec<Locale> = old.locales().chain(new.locales()).cloned().collect();
+    locales.sort();
+    locales.dedup();
+    let statement = |body: &ProblemBody, locale: &Locale| {
+        body.locales().any(|other| other == locale).then(|| body.localized(&locale.0))
+    };
+    let format_changed = old.format != new.format;
+    locales
+        .into_iter()
+        .filter(|locale| format_changed || statement(old, locale) != statement(new, locale))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::{diff_problems, JudgingSnapshot, ProblemChange, ProblemRevision};
+    use crate::{
+        checker::LiteralCheckMode,
+        problem::{Checker, EditablePartsOfProblem, Locale, ProblemId, ProblemStatement},
+        problemset::Tag,
+    };
+
+    #[test]
+    fn diff_lists_changed_fields() {
+        let old = EditablePartsOfProblem {
+            test_cases: vec![Uuid::new_v4(), Uuid::new_v4()],
+            tags: vec![Tag::Math],
+            time_limit: 1,
+            memory_limit: 256,
+            ..Default::default()
+        };
+        let mut new = old.clone();
+        new.body.translations.insert(
+            Locale("en".to_string()),
+            ProblemStatement { name: "Sum".to_string(), ..Default::default() },
+        );
+        new.tags = vec![Tag::Greedy];
+        new.time_limit = 2;
+        new.test_cases.reverse();
+
+        assert_eq!(
+            diff_problems(&old, &new),
+            vec![
+                ProblemChange::Statement { locales: vec![Locale("en".to_string())] },
+                ProblemChange::Tags { added: vec![Tag::Greedy], removed: vec![Tag::Math] },
+                ProblemChange::TimeLimit { from: 1, to: 2 },
+                ProblemChange::TestCases { added: vec![], removed: vec![], reordered: true },
+            ]
+        );
+
+        let first = ProblemRevision::first(ProblemId(1), &old, Uuid::new_v4(), Utc::now());
+        assert!(first.next(&old, &old, Uuid::new_v4(), Utc::now()).is_none());
+        let next = first.next(&old, &new, Uuid::new_v4(), Utc::now()).unwrap();
+        assert_eq!(next.revision, 2);
+        assert!(next.requires_rejudge());
+    }
+
+    #[test]
+    fn old_revisions_are_rebuilt_from_their_snapshot() {
+        let original = EditablePartsOfProblem {
+            test_cases: vec![Uuid::new_v4(), Uuid::new_v4()],
+            time_limit: 1,
+            memory_limit: 256,
+            ..Default::default()
+        };
+        let first = ProblemRevision::first(ProblemId(1), &original, Uuid::new_v4(), Utc::now());
+
+        let mut current = original.clone();
+        current.time_limit = 3;
+        current.memory_limit = 512;
+        current.test_cases.reverse();
+        current.test_cases.push(Uuid::new_v4());
+        current.checker = Some(Checker::literal(LiteralCheckMode::Exact));
+        current.tags = vec![Tag::Math];
+        let second = first.next(&original, &current, Uuid::new_v4(), Utc::now()).unwrap();
+
+        let rebuilt = first.rebuild(&current);
+        assert_eq!(JudgingSnapshot::from(&rebuilt), JudgingSnapshot::from(&original));
+        assert_eq!(rebuilt.tags, current.tags);
+        assert!(diff_problems(&original, &rebuilt).iter().all(|change| !change.affects_verdicts()));
+        assert_eq!(second.rebuild(&original).test_cases, current.test_cases);
+    }
+}
@@ -0,0 +1,219 @@
+//! Solutions written by the problem setter, run against every test case before a problem is
+//! published to check the limits are neither too tight nor too loose.
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    consts::MAX_SUBMISSION_FILE_SIZE_IN_BYTES, contest::Language, problem::ProblemId,
+    status::Status,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[ts(export)]
+pub enum ExpectedVerdict {
+    /// passes every test case
+    #[default]
+    Accepted,
+    /// exceeds the time limit on some test case and passes the rest
+    TimeLimitExceeded,
+    /// gets a wrong answer on some test case and passes the rest
+    WrongAnswer,
+    /// only run to compare times, any verdict is fine
+    Any,
+}
+
+impl ExpectedVerdict {
+    /// Whether a solution that got `statuses` on the test cases behaves as expected.
+    pub fn is_satisfied_by<'a>(&self, statuses: impl IntoIterator<Item = &'a Status>) -> bool {
+        let expected = match self {
+            ExpectedVerdict::Accepted => return statuses.into_iter().all(is_accepted),
+            ExpectedVerdict::TimeLimitExceeded => Status::TimeLimitExceeded,
+            ExpectedVerdict::WrongAnswer => Status::WrongAnswer,
+            ExpectedVerdict::Any => return true,
+        };
+        let mut found = false;
+        for status in statuses {
+            if *status == expected {
+                found = true;
+            } else if !is_accepted(status) {
+                return false;
+            }
+        }
+        found
+    }
+}
+
+fn is_accepted(status: &Status) -> bool {
+    *status == Status::Accepted
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize, Validate)]
+#[ts(export)]
+pub struct ProblemSolution {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    pub language: Language,
+    #[validate(custom = "validate_solution_source")]
+    pub source: String,
+    pub expected: ExpectedVerdict,
+}
+
+fn validate_solution_source(source: &str) -> Result<(), ValidationError> {
+    if source.is_empty() || source.len() > MAX_SUBMISSION_FILE_SIZE_IN_BYTES {
+        return Err(ValidationError::new("invalid_solution_size"));
+    }
+    Ok(())
+}
+
+/// Result of one solution on one test case.
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct InvocationResult {
+    pub test_case_id: Uuid,
+    pub status: Status,
+    #[ts(type = "number")]
+    pub time_ms: u64,
+    #[ts(type = "number")]
+    pub memory_kb: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct SolutionInvocation {
+    pub name: String,
+    pub expected: ExpectedVerdict,
+    /// in test case order, missing test cases were not run yet
+    pub results: Vec<InvocationResult>,
+}
+
+impl SolutionInvocation {
+    pub fn is_expected(&self) -> bool {
+        self.expected.is_satisfied_by(self.results.iter().map(|result| &result.status))
+    }
+
+    pub fn max_time_ms(&self) -> u64 {
+        self.results.iter().map(|result| result.time_ms).max().unwrap_or_default()
+    }
+
+    pub fn max_memory_kb(&self) -> u64 {
+        self.results.iter().map(|result| result.memory_kb).max().unwrap_or_default()
+    }
+}
+
+/// Table of every setter solution against every test case, rows are solutions and columns
+/// are `test_cases`.
+#[derive(Debug, Clone, PartialEq, Eq, TS, Serialize, Deserialize)]
+#[ts(export)]
+pub struct InvocationReport {
+    pub problem_id: ProblemId,
+    pub test_cases: Vec<Uuid>,
+    pub solutions: Vec<SolutionInvocation>,
+}
+
+impl InvocationReport {
+    pub fn new(
+        problem_id: ProblemId,
+        test_cases: Vec<Uuid>,
+        solutions: &[ProblemSolution],
+    ) -> Self {
+        Self {
+            problem_id,
+            test_cases,
+            solutions: solutions
+                .iter()
+                .map(|solution| SolutionInvocation {
+                    name: solution.name.clone(),
+                    expected: solution.expected,
+                    results: Vec::new(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Stores the result of the solution called `name`, replacing any previous one for the same
+    /// test case.
+    pub fn record(&mut self, name: &str, result: InvocationResult) {
+        let Some(solution) = self.solutions.iter_mut().find(|solution| solution.name == name)
+        else {
+            return;
+        };
+        solution.results.retain(|other| other.test_case_id != result.test_case_id);
+        solution.results.push(result);
+        let position = |id: &Uuid| self.test_cases.iter().position(|test_case| test_case == id);
+        solution.results.sort_by_key(|result| position(&result.test_case_id));
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.solutions.iter().all(|solution| solution.results.len() == self.test_cases.len())
+    }
+
+    /// Solutions that did not get their expected verdict.
+    pub fn unexpected(&self) -> impl Iterator<Item = &SolutionInvocation> {
+        self.solutions.iter().filter(|solution| !solution.is_expected())
+    }
+
+    /// Slowest test case of the slowest solution expected to be accepted, compare it with the
+    /// time limit to know how tight it is.
+    pub fn max_accepted_time_ms(&self) -> Option<u64> {
+        self.solutions
+            .iter()
+            .filter(|solution| solution.expected == ExpectedVerdict::Accepted)
+            .map(SolutionInvocation::max_time_ms)
+            .max()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{ExpectedVerdict, InvocationReport, InvocationResult, ProblemSolution};
+    use crate::{contest::Language, problem::ProblemId, status::Status};
+
+    fn solution(name: &str, expected: ExpectedVerdict) -> ProblemSolution {
+        ProblemSolution {
+            name: name.to_string(),
+            language: Language::Cpp17,
+            source: "int main() {}".to_string(),
+            expected,
+        }
+    }
+
+    #[test]
+    fn report_flags_solutions_with_unexpected_verdicts() {
+        let test_cases = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let solutions = [
+            solution("main", ExpectedVerdict::Accepted),
+            solution("brute", ExpectedVerdict::TimeLimitExceeded),
+            solution("greedy", ExpectedVerdict::WrongAnswer),
+        ];
+        let mut report = InvocationReport::new(ProblemId(1), test_cases.clone(), &solutions);
+        let results = [
+            ("main", [(Status::Accepted, 300), (Status::Accepted, 700)]),
+            ("brute", [(Status::Accepted, 900), (Status::TimeLimitExceeded, 2000)]),
+            ("greedy", [(Status::Accepted, 10), (Status::Accepted, 20)]),
+        ];
+        for (name, statuses) in results {
+            for (test_case_id, (status, time_ms)) in test_cases.iter().zip(statuses).rev() {
+                let result = InvocationResult {
+                    test_case_id: *test_case_id,
+                    status,
+                    time_ms,
+                    memory_kb: 1024,
+                };
+                report.record(name, result);
+            }
+        }
+
+        assert!(report.is_complete());
+        assert_eq!(report.solutions[0].results[0].test_case_id, test_cases[0]);
+        assert_eq!(
+            report.unexpected().map(|solution| solution.name.as_str()).collect::<Vec<_>>(),
+            vec!["greedy"]
+        );
+        assert_eq!(report.max_accepted_time_ms(), Some(700));
+    }
+}
@@ -16,6 +16,8 @@ pub enum Status {
     RuntimeError,
     PartialPoints,
     CompilationError,
+    /// interactive problems only, the program waited for input for too long
+    IdlenessLimitExceeded,
     UnknownError(String),
 }
 
@@ -31,6 +33,9 @@ pub enum StatusPG {
     TimeLimitExceeded,
     CompilationError,
     PartialPoints,
+    /// databases created before this label existed need
+    /// `ALTER TYPE submission_status ADD VALUE 'idleness_limit_exceeded'`
+    IdlenessLimitExceeded,
     UnknownError,
 }
 impl fmt::Display for Status {
@@ -41,6 +46,7 @@ impl fmt::Display for Status {
             Status::TimeLimitExceeded => write!(f, "Time Limit Exceeded"),
             Status::PartialPoints => write!(f, "Partial Execution"),
             Status::RuntimeError => write!(f, "Runtime Error"),
+            Status::IdlenessLimitExceeded => write!(f, "Idleness Limit Exceeded"),
             Status::UnknownError(e) => write!(f, "Unknown Error:({})", e),
             Status::CompilationError => write!(f, "Compilation Error"),
             Status::Pending => write!(f, "Pending"),
@@ -48,6 +54,7 @@ impl fmt::Display for Status {
     }
 }
 
+#[allow(clippy::infallible_try_from)]
 impl TryFrom<String> for Status {
     type Error = std::convert::Infallible;
 
@@ -57,6 +64,9 @@ impl TryFrom<String> for Status {
             "Wrong Answer" | "wrong_answer" => Ok(Status::WrongAnswer),
             "Time Limit Exceeded" | "time_limit_exceeded" => Ok(Status::TimeLimitExceeded),
             "Runtime Error" | "runtime_error" => Ok(Status::RuntimeError),
+            "Idleness Limit Exceeded" | "idleness_limit_exceeded" => {
+                Ok(Status::IdlenessLimitExceeded)
+            },
             "Pending" | "pending" => Ok(Status::Pending),
             "Compilation Error" | "compilation_error" => Ok(Status::CompilationError),
             "Unknown Error" | "unknown_error" => Ok(Status::UnknownError("".to_string())),
@@ -72,6 +82,7 @@ impl fmt::Display for StatusPG {
             StatusPG::TimeLimitExceeded => write!(f, "Time Limit Exceeded"),
             StatusPG::PartialPoints => write!(f, "Partial Execution"),
             StatusPG::RuntimeError => write!(f, "Runtime Error"),
+            StatusPG::IdlenessLimitExceeded => write!(f, "Idleness Limit Exceeded"),
             StatusPG::UnknownError => write!(f, "UnknownError"),
             StatusPG::CompilationError => write!(f, "Compilation Error"),
             StatusPG::Pending => write!(f, "Pending"),
@@ -86,6 +97,7 @@ lazy_static! {
         (Status::WrongAnswer, 2),
         (Status::TimeLimitExceeded, 3),
         (Status::RuntimeError, 4),
+        (Status::IdlenessLimitExceeded, 5),
     ]);
 }
 
@@ -96,10 +108,20 @@ back_to_enum! {
         Accepted = 0,
         WrongAnswer = 1,
         FormatError = 2,
+        Fail = 3,
         PartialExecution = 7,
     }
 }
 
+back_to_enum! {
+    #[derive(Debug)]
+    #[repr(i32)]
+    pub enum KattisExitCodes {
+        Accepted = 42,
+        WrongAnswer = 43,
+    }
+}
+
 back_to_enum! {
     #[derive(Debug)]
     #[repr(i32)]
@@ -0,0 +1,236 @@
+//! Content addressed storage for test case files.
+//!
+//! Files are identified by the sha256 of their contents, so the same input shared by several
+//! test cases (or problems) is stored once.
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::{
+    consts::MAX_TESCASE_FILE_SIZE_IN_BYTES,
+    problem::{ProblemId, TestCaseInfo},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Deserialize, Serialize, TS)]
+#[ts(export)]
+pub struct TestCaseFileMetadata {
+    /// hex encoded sha256 of the contents
+    pub sha256: String,
+    pub size: u64,
+    pub line_count: u64,
+    pub trailing_newline: bool,
+}
+
+impl TestCaseFileMetadata {
+    pub fn from_bytes(contents: &[u8]) -> Self {
+        let trailing_newline = contents.last() == Some(&b'\n');
+        let newlines = contents.iter().filter(|byte| **byte == b'\n').count() as u64;
+        Self {
+            sha256: hex::encode(Sha256::digest(contents)),
+            size: contents.len() as u64,
+            line_count: if contents.is_empty() || trailing_newline {
+                newlines
+            } else {
+                newlines + 1
+            },
+            trailing_newline,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TestCaseStoreError {
+    TooLarge { size: u64, max: u64 },
+    InvalidDigest(String),
+    NotFound(String),
+    Corrupted(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for TestCaseStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestCaseStoreError::TooLarge { size, max } => {
+                write!(f, "test case file of {} bytes exceeds the limit of {} bytes", size, max)
+            },
+            TestCaseStoreError::InvalidDigest(sha256) => {
+                write!(f, "{:?} is not a sha256 digest", sha256)
+            },
+            TestCaseStoreError::NotFound(sha256) => {
+                write!(f, "test case file {} not found", sha256)
+            },
+            TestCaseStoreError::Corrupted(sha256) => {
+                write!(f, "test case file {} does not match its digest", sha256)
+            },
+            TestCaseStoreError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TestCaseStoreError {}
+
+impl From<io::Error> for TestCaseStoreError {
+    fn from(value: io::Error) -> Self {
+        TestCaseStoreError::Io(value)
+    }
+}
+
+pub trait TestCaseStore {
+    /// Stores `contents` unless a file with the same digest already exists.
+    fn put(&self, contents: &[u8]) -> Result<TestCaseFileMetadata, TestCaseStoreError>;
+
+    fn get(&self, sha256: &str) -> Result<Vec<u8>, TestCaseStoreError>;
+
+    fn contains(&self, sha256: &str) -> bool;
+
+    /// Location of the file with digest `sha256`, as written in `TestCaseInfo` paths.
+    fn locate(&self, sha256: &str) -> Result<String, TestCaseStoreError>;
+
+    fn put_test_case(
+        &self,
+        problem_id: ProblemId,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<TestCaseInfo, TestCaseStoreError> {
+        let stdin = self.put(input)?;
+        let stdout = self.put(output)?;
+        Ok(TestCaseInfo {
+            stdin_path: self.locate(&stdin.sha256)?,
+            stdout_path: self.locate(&stdout.sha256)?,
+            problem_id,
+            id: Uuid::new_v4(),
+            stdin_metadata: Some(stdin),
+            stdout_metadata: Some(stdout),
+            ..Default::default()
+        })
+    }
+
+    /// Reads the file back and checks it still matches `metadata`.
+    fn verify(&self, metadata: &TestCaseFileMetadata) -> Result<(), TestCaseStoreError> {
+        let contents = self.get(&metadata.sha256)?;
+        if TestCaseFileMetadata::from_bytes(&contents) == *metadata {
+            Ok(())
+        } else {
+            Err(TestCaseStoreError::Corrupted(metadata.sha256.clone()))
+        }
+    }
+}
+
+/// Stores files as `<root>/<first two hex digits>/<sha256>`.
+#[derive(Debug, Clone)]
+pub struct FileSystemTestCaseStore {
+    root: PathBuf,
+}
+
+impl FileSystemTestCaseStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Digests come from callers, anything but 64 lowercase hex digits could point outside
+    /// `root`.
+    fn path(&self, sha256: &str) -> Result<PathBuf, TestCaseStoreError> {
+        if sha256.len() != 64 || !sha256.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            return Err(TestCaseStoreError::InvalidDigest(sha256.to_string()));
+        }
+        Ok(self.root.join(&sha256[..2]).join(sha256))
+    }
+}
+
+impl TestCaseStore for FileSystemTestCaseStore {
+    fn put(&self, contents: &[u8]) -> Result<TestCaseFileMetadata, TestCaseStoreError> {
+        check_size(contents.len() as u64)?;
+        let metadata = TestCaseFileMetadata::from_bytes(contents);
+        let path = self.path(&metadata.sha256)?;
+        if !path.is_file() {
+            fs::create_dir_all(path.parent().unwrap_or(&self.root))?;
+            // write to a temporary file first so a crash never leaves a truncated file under
+            // a valid digest
+            let temporary = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+            fs::write(&temporary, contents)?;
+            fs::rename(&temporary, &path)?;
+        }
+        Ok(metadata)
+    }
+
+    fn get(&self, sha256: &str) -> Result<Vec<u8>, TestCaseStoreError> {
+        let path = self.path(sha256)?;
+        if !path.is_file() {
+            return Err(TestCaseStoreError::NotFound(sha256.to_string()));
+        }
+        check_size(fs::metadata(&path)?.len())?;
+        Ok(fs::read(path)?)
+    }
+
+    fn contains(&self, sha256: &str) -> bool {
+        self.path(sha256).is_ok_and(|path| path.is_file())
+    }
+
+    fn locate(&self, sha256: &str) -> Result<String, TestCaseStoreError> {
+        Ok(self.path(sha256)?.to_string_lossy().into_owned())
+    }
+}
+
+pub fn check_size(size: u64) -> Result<(), TestCaseStoreError> {
+    let max = MAX_TESCASE_FILE_SIZE_IN_BYTES as u64;
+    if size > max {
+        Err(TestCaseStoreError::TooLarge { size, max })
+    } else {
+        Ok(())
+    }
+}
+
+/// Metadata of a test case file already on disk, i.e. one referenced by `stdin_path`.
+pub fn metadata_of(path: &Path) -> Result<TestCaseFileMetadata, TestCaseStoreError> {
+    check_size(fs::metadata(path)?.len())?;
+    Ok(TestCaseFileMetadata::from_bytes(&fs::read(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use uuid::Uuid;
+
+    use super::{FileSystemTestCaseStore, TestCaseFileMetadata, TestCaseStore, TestCaseStoreError};
+    use crate::{consts::MAX_TESCASE_FILE_SIZE_IN_BYTES, problem::ProblemId};
+
+    #[test]
+    fn file_metadata_is_computed() {
+        let metadata = TestCaseFileMetadata::from_bytes(b"1 2\n3 4\n");
+        assert_eq!(metadata.size, 8);
+        assert_eq!(metadata.line_count, 2);
+        assert!(metadata.trailing_newline);
+
+        let metadata = TestCaseFileMetadata::from_bytes(b"1 2\n3 4");
+        assert_eq!(metadata.line_count, 2);
+        assert!(!metadata.trailing_newline);
+        assert_eq!(TestCaseFileMetadata::from_bytes(b"").line_count, 0);
+    }
+
+    #[test]
+    fn file_system_store_deduplicates_by_digest() {
+        let root = std::env::temp_dir().join(format!("test-cases-{}", Uuid::new_v4()));
+        let store = FileSystemTestCaseStore::new(&root);
+
+        let first = store.put_test_case(ProblemId(1), b"1 2\n", b"3\n").unwrap();
+        let second = store.put_test_case(ProblemId(2), b"1 2\n", b"3\n").unwrap();
+        assert_eq!(first.stdin_path, second.stdin_path);
+        assert_ne!(first.id, second.id);
+        store.verify(first.stdin_metadata.as_ref().unwrap()).unwrap();
+
+        let too_large = vec![b'0'; MAX_TESCASE_FILE_SIZE_IN_BYTES + 1];
+        assert!(matches!(store.put(&too_large), Err(TestCaseStoreError::TooLarge { .. })));
+        for digest in ["../../etc/passwd", "é", &"A".repeat(64)] {
+            assert!(matches!(store.get(digest), Err(TestCaseStoreError::InvalidDigest(_))));
+            assert!(!store.contains(digest));
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+}
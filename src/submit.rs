@@ -1,14 +1,24 @@
+use std::{
+    collections::BTreeMap,
+    io::{Read, Seek},
+};
+
+use anyhow::{anyhow, Context, Result};
 use bit_vec::BitVec;
 use serde::{
     de::{self},
     Deserialize, Deserializer, Serialize,
 };
 use ts_rs::TS;
+use uuid::Uuid;
+use zip::ZipArchive;
 
 use crate::{
+    consts::MAX_TESCASE_FILE_SIZE_IN_BYTES,
     contest::Language,
-    problem::{ProblemId, SubmissionId},
+    problem::{ProblemId, SubmissionId, TestCaseInfo, ValidationType},
     status::StatusPG,
+    storage::check_size,
     utils::empty_string_as_none,
 };
 
@@ -23,6 +33,67 @@ pub struct SubmitForm {
     pub contest_id: Option<u32>,
 }
 
+/// What gets evaluated, `OutputOnly` problems take the outputs instead of a program.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SubmissionPayload {
+    /// `RunTwice` problems compile it once and run it twice per test case
+    Source { language: Language, code: String },
+    /// contestant outputs keyed by test case id, tests without an output are wrong answers
+    OutputOnly { outputs: BTreeMap<Uuid, Vec<u8>> },
+}
+
+impl SubmissionPayload {
+    /// Reads an output only submission, a zip with files named after the test case ordinal
+    /// (`1.out`, `01.txt`, ...) at any depth.
+    pub fn output_only<R: Read + Seek>(archive: R, test_cases: &[TestCaseInfo]) -> Result<Self> {
+        let mut zip = ZipArchive::new(archive).context("submission is not a zip")?;
+        let mut outputs = BTreeMap::new();
+        for index in 0..zip.len() {
+            let mut file = zip.by_index(index)?;
+            if file.is_dir() {
+                continue;
+            }
+            let name = file.name().rsplit('/').next().unwrap_or_default().to_string();
+            let test_case = name
+                .split('.')
+                .next()
+                .and_then(|ordinal| ordinal.parse::<u32>().ok())
+                .and_then(|ordinal| test_cases.iter().find(|test| test.ordinal == ordinal))
+                .ok_or_else(|| anyhow!("{} does not match any test case", name))?;
+            // the size in the header is not enforced when decompressing, so read one byte past
+            // the limit to catch entries that lie about it
+            check_size(file.size())?;
+            let mut output = Vec::with_capacity(file.size() as usize);
+            file.by_ref()
+                .take(MAX_TESCASE_FILE_SIZE_IN_BYTES as u64 + 1)
+                .read_to_end(&mut output)?;
+            check_size(output.len() as u64)?;
+            if outputs.insert(test_case.id, output).is_some() {
+                return Err(anyhow!("test case {} has more than one output", test_case.ordinal));
+            }
+        }
+        Ok(SubmissionPayload::OutputOnly { outputs })
+    }
+
+    pub fn is_valid_for(&self, validation: &ValidationType) -> bool {
+        match self {
+            SubmissionPayload::Source { language, .. } => {
+                *language != Language::Cmp && !matches!(validation, ValidationType::OutputOnly)
+            },
+            SubmissionPayload::OutputOnly { .. } => {
+                matches!(validation, ValidationType::OutputOnly)
+            },
+        }
+    }
+}
+
+impl From<&SubmitForm> for SubmissionPayload {
+    fn from(form: &SubmitForm) -> Self {
+        SubmissionPayload::Source { language: form.language.clone(), code: form.code.clone() }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, TS)]
 #[serde(rename_all = "lowercase")]
 #[ts(export)]
@@ -76,3 +147,65 @@ pub struct GetSubmissionsJson {
 pub struct SubmitResponse {
     pub submission_id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Write};
+
+    use uuid::Uuid;
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::SubmissionPayload;
+    use crate::{
+        consts::MAX_TESCASE_FILE_SIZE_IN_BYTES,
+        problem::{TestCaseInfo, ValidationType},
+    };
+
+    fn zip(files: &[(&str, &str)]) -> Cursor<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents) in files {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        Cursor::new(zip.finish().unwrap().into_inner())
+    }
+
+    #[test]
+    fn output_only_submission_is_keyed_by_test_case() {
+        let test_cases: Vec<_> = (1..=2)
+            .map(|ordinal| TestCaseInfo { id: Uuid::new_v4(), ordinal, ..Default::default() })
+            .collect();
+
+        let payload = SubmissionPayload::output_only(
+            zip(&[("outputs/01.out", "3\n"), ("2.txt", "5\n")]),
+            &test_cases,
+        )
+        .unwrap();
+        let SubmissionPayload::OutputOnly { outputs } = &payload else {
+            panic!("expected an output only payload");
+        };
+        assert_eq!(outputs[&test_cases[0].id], b"3\n");
+        assert_eq!(outputs[&test_cases[1].id], b"5\n");
+        assert!(payload.is_valid_for(&ValidationType::OutputOnly));
+        assert!(!payload.is_valid_for(&ValidationType::RunTwice));
+
+        assert!(SubmissionPayload::output_only(zip(&[("3.out", "")]), &test_cases).is_err());
+        assert!(SubmissionPayload::output_only(zip(&[("1.out", ""), ("01.txt", "")]), &test_cases)
+            .is_err());
+    }
+
+    #[test]
+    fn output_only_entries_are_bounded_by_their_contents() {
+        let test_cases = [TestCaseInfo { id: Uuid::new_v4(), ordinal: 1, ..Default::default() }];
+        let output = "0".repeat(MAX_TESCASE_FILE_SIZE_IN_BYTES + 1);
+        let mut bomb = zip(&[("1.out", &output)]).into_inner();
+        // claim a one byte output in the local header and in the central directory
+        for (signature, offset) in [(b"PK\x03\x04", 22), (b"PK\x01\x02", 24)] {
+            let header = bomb.windows(4).position(|window| window == signature).unwrap();
+            bomb[header + offset..header + offset + 4].copy_from_slice(&1u32.to_le_bytes());
+        }
+
+        let error = SubmissionPayload::output_only(Cursor::new(bomb), &test_cases).unwrap_err();
+        assert!(error.to_string().contains("exceeds the limit"));
+    }
+}
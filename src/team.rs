@@ -0,0 +1,99 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{
+    consts::{TEAM_MAX_MEMBERS, TEAM_MIN_MEMBERS},
+    contest::Submission,
+};
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, TS, Validate, sqlx::FromRow)]
+#[ts(export)]
+pub struct Team {
+    pub id: Uuid,
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    #[validate(custom = "validate_team_members")]
+    pub members: Vec<Uuid>,
+    pub institution: Option<String>,
+    /// ISO 3166-1 alpha-2 code, i.e. `MX`
+    #[validate(custom = "validate_country_code")]
+    pub country: Option<String>,
+}
+
+impl Team {
+    pub fn is_member(&self, user_id: &Uuid) -> bool {
+        self.members.contains(user_id)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS, Validate)]
+#[ts(export)]
+pub struct TeamForm {
+    #[validate(length(min = 1, max = 50))]
+    pub name: String,
+    #[validate(custom = "validate_team_members")]
+    pub members: Vec<Uuid>,
+    #[serde(default)]
+    #[validate(length(max = 100))]
+    pub institution: Option<String>,
+    #[serde(default)]
+    #[validate(custom = "validate_country_code")]
+    pub country: Option<String>,
+}
+
+impl TeamForm {
+    pub fn into_team(self, id: Uuid) -> Team {
+        Team {
+            id,
+            name: self.name,
+            members: self.members,
+            institution: self.institution,
+            country: self.country,
+        }
+    }
+}
+
+/// Whom a submission counts for in the scoreboard.
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Deserialize, Serialize, TS)]
+#[serde(tag = "type", content = "id", rename_all = "lowercase")]
+#[ts(export)]
+pub enum ContestParticipant {
+    User(Uuid),
+    Team(Uuid),
+}
+
+impl From<&Submission> for ContestParticipant {
+    fn from(submission: &Submission) -> Self {
+        match submission.team_id {
+            Some(team_id) => ContestParticipant::Team(team_id),
+            None => ContestParticipant::User(submission.user_id),
+        }
+    }
+}
+
+fn validate_team_members(members: &[Uuid]) -> Result<(), ValidationError> {
+    if !(TEAM_MIN_MEMBERS..=TEAM_MAX_MEMBERS).contains(&members.len()) {
+        let mut error = ValidationError::new("team_size");
+        error.add_param("min".into(), &TEAM_MIN_MEMBERS);
+        error.add_param("max".into(), &TEAM_MAX_MEMBERS);
+        error.add_param("value".into(), &members.len());
+        return Err(error);
+    }
+    let unique: HashSet<_> = members.iter().collect();
+    if unique.len() != members.len() {
+        return Err(ValidationError::new("repeated_member"));
+    }
+    Ok(())
+}
+
+fn validate_country_code(country: &str) -> Result<(), ValidationError> {
+    if country.len() == 2 && country.chars().all(|c| c.is_ascii_uppercase()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_country"))
+    }
+}